@@ -7,7 +7,7 @@ pub trait PaperApp {
     where
         Self: Sized;
 
-    fn setup(&mut self, cmd: Commands) {}
+    fn setup(&mut self, _cmd: Commands) {}
 
     fn update(&mut self, cmd: Commands);
 
@@ -16,6 +16,15 @@ pub trait PaperApp {
     fn cleanup(&mut self, _cmd: Commands) {}
 
     fn event_handler(&mut self, _cmd: Commands, _events: &[Event]) {}
+
+    /// Called after [`Event::Close`] was handled, e.g. when the close button of the window was clicked.
+    /// Returning `false` keeps the window open, [`Commands::close`] can be used to close it later.
+    ///
+    /// While replaying, the close button is still checked here even though its event is replaced by
+    /// the recorded ones.
+    fn on_close_requested(&mut self, _cmd: Commands) -> bool {
+        true
+    }
 }
 
 pub struct EmptyApp;
@@ -1,8 +1,12 @@
-use paper_input::Event;
+use std::any::TypeId;
+
+use log::error;
+use paper_input::{Event, Gamepad, GamepadAxis, GamepadButton, InputMap, InputState, Key, MouseButton};
 use paper_math::{Quat, Transform, Vec2, Vec3};
-use paper_render::{Material, Mesh, ShaderUniform};
+use paper_render::{Material, Mesh, ShaderUniform, Svg};
+use paper_window::{Cursor, CursorMode, Monitor, RgbaImage, Window, WindowMode};
 
-use crate::{Entity, EntityId, MaterialId, MeshId};
+use crate::{Entity, EntityId, Gizmos, MaterialId, MeshId, Resources, State, StateStack, UserEvents};
 
 pub(crate) trait Commandable {
     fn close(&mut self);
@@ -13,12 +17,60 @@ pub(crate) trait Commandable {
 
     fn fixed_delta_time(&self) -> f32;
 
+    fn window(&self) -> &Window;
+
+    fn window_mut(&mut self) -> &mut Window;
+
+    fn is_replaying(&self) -> bool;
+
+    fn send_event(&mut self, event: Event);
+
+    fn register_user_event(&mut self, type_id: TypeId, update: fn(&mut Resources));
+
+    fn resources(&self) -> &Resources;
+
+    fn resources_mut(&mut self) -> &mut Resources;
+
+    fn set_system_enabled(&mut self, label: &str, enabled: bool);
+
+    fn is_system_enabled(&self, label: &str) -> bool;
+
+    fn clipboard(&self) -> Option<String>;
+
+    fn set_clipboard(&mut self, text: &str);
+
+    fn input(&self) -> &InputState;
+
+    fn cursor_mode(&self) -> CursorMode;
+
+    fn set_cursor_mode(&mut self, mode: CursorMode);
+
+    fn set_raw_mouse_motion(&mut self, enabled: bool) -> bool;
+
+    fn set_cursor(&mut self, cursor: &Cursor);
+
+    fn input_map(&self) -> &InputMap;
+
+    fn input_map_mut(&mut self) -> &mut InputMap;
+
     fn add_mesh(&mut self, mesh: Mesh) -> MeshId;
 
+    fn add_mesh_deduplicated(&mut self, mesh: Mesh) -> MeshId;
+
+    fn update_mesh(&mut self, mesh_id: MeshId, mesh: Mesh) -> bool;
+
+    fn remove_mesh(&mut self, mesh_id: MeshId) -> bool;
+
     fn add_material(&mut self, material: Box<dyn Material>) -> MaterialId;
 
+    fn remove_material(&mut self, material_id: MaterialId) -> bool;
+
     fn add_entity(&mut self, entity: Entity) -> EntityId;
 
+    fn add_svg(&mut self, svg: &Svg, transform: Transform) -> EntityId;
+
+    fn gizmos(&mut self) -> &mut Gizmos;
+
     fn remove_entity(&mut self, entity_id: EntityId) -> Option<Entity>;
 
     fn set_material_uniform(&mut self, material_id: MaterialId, name: &str, value: ShaderUniform);
@@ -39,6 +91,7 @@ impl<'a> Commands<'a> {
         Self { ca }
     }
 
+    /// Closes the window at the end of the frame, this also confirms a pending close request.
     pub fn close(&mut self) {
         self.ca.close();
     }
@@ -55,10 +108,336 @@ impl<'a> Commands<'a> {
         self.ca.fixed_delta_time()
     }
 
+    /// Whether the input comes from a [`Recording`](crate::Recording) instead of the window.
+    pub fn is_replaying(&self) -> bool {
+        self.ca.is_replaying()
+    }
+
+    /// Queues an event that is processed like an event from the window at the start of the next frame.
+    pub fn send_event(&mut self, event: Event) {
+        self.ca.send_event(event);
+    }
+
+    /// Sends a user event, which can be read through [`Commands::read_user_events`] during the next frame.
+    pub fn send_user_event<E: 'static>(&mut self, event: E) {
+        if !self.has_resource::<UserEvents<E>>() {
+            self.ca.register_user_event(TypeId::of::<E>(), UserEvents::<E>::update_resource);
+        }
+        self.init_resource::<UserEvents<E>>().send(event);
+    }
+
+    /// The user events of type `E` sent during the previous frame.
+    pub fn read_user_events<E: 'static>(&self) -> &[E] {
+        self.resource::<UserEvents<E>>().map(UserEvents::read).unwrap_or_default()
+    }
+
+    /// Inserts a resource, replacing the previous one of the same type.
+    pub fn insert_resource<R: 'static>(&mut self, resource: R) {
+        self.ca.resources_mut().insert(resource);
+    }
+
+    pub fn init_resource<R: Default + 'static>(&mut self) -> &mut R {
+        self.ca.resources_mut().get_or_insert_with(R::default)
+    }
+
+    pub fn resource<R: 'static>(&self) -> Option<&R> {
+        self.ca.resources().get()
+    }
+
+    pub fn resource_mut<R: 'static>(&mut self) -> Option<&mut R> {
+        self.ca.resources_mut().get_mut()
+    }
+
+    pub fn remove_resource<R: 'static>(&mut self) -> Option<R> {
+        self.ca.resources_mut().remove()
+    }
+
+    pub fn has_resource<R: 'static>(&self) -> bool {
+        self.ca.resources().contains::<R>()
+    }
+
+    pub fn resources(&self) -> &Resources {
+        self.ca.resources()
+    }
+
+    pub fn resources_mut(&mut self) -> &mut Resources {
+        self.ca.resources_mut()
+    }
+
+    /// The current state of type `S`, `None` if no such state was added.
+    pub fn state<S: State>(&self) -> Option<&S> {
+        self.resource::<StateStack<S>>().and_then(StateStack::current)
+    }
+
+    pub fn in_state<S: State>(&self, state: &S) -> bool {
+        self.state::<S>() == Some(state)
+    }
+
+    /// Exits all active states of type `S` and enters `state` at the start of the next frame.
+    pub fn set_state<S: State>(&mut self, state: S) {
+        if let Some(stack) = self.state_stack_mut::<S>() {
+            stack.set(state);
+        }
+    }
+
+    /// Enters `state` on top of the current state at the start of the next frame, e.g. for a pause menu.
+    pub fn push_state<S: State>(&mut self, state: S) {
+        if let Some(stack) = self.state_stack_mut::<S>() {
+            stack.push(state);
+        }
+    }
+
+    /// Exits the current state of type `S` at the start of the next frame.
+    pub fn pop_state<S: State>(&mut self) {
+        if let Some(stack) = self.state_stack_mut::<S>() {
+            stack.pop();
+        }
+    }
+
+    /// Enables or disables all systems with the given label.
+    pub fn set_system_enabled(&mut self, label: &str, enabled: bool) {
+        self.ca.set_system_enabled(label, enabled);
+    }
+
+    pub fn is_system_enabled(&self, label: &str) -> bool {
+        self.ca.is_system_enabled(label)
+    }
+
+    pub fn set_window_title(&mut self, title: &str) {
+        self.ca.window_mut().set_title(title);
+    }
+
+    /// Size of the window in screen coordinates, see [`Commands::logical_size`] for drawing.
+    pub fn window_size(&self) -> (u32, u32) {
+        self.ca.window().size()
+    }
+
+    /// Ratio between physical and logical pixels. Changes are reported through [`Event::ContentScale`].
+    pub fn scale_factor(&self) -> f32 {
+        self.ca.window().scale_factor()
+    }
+
+    /// Size of the framebuffer in physical pixels.
+    pub fn framebuffer_size(&self) -> (u32, u32) {
+        self.ca.window().framebuffer_size()
+    }
+
+    /// Size of the framebuffer in logical pixels, the units used by the camera and the mouse position.
+    pub fn logical_size(&self) -> (f32, f32) {
+        self.ca.window().logical_size()
+    }
+
+    pub fn set_window_size(&mut self, width: u32, height: u32) {
+        self.ca.window_mut().set_size(width, height);
+    }
+
+    pub fn window_position(&self) -> (i32, i32) {
+        self.ca.window().position()
+    }
+
+    pub fn set_window_position(&mut self, x: i32, y: i32) {
+        self.ca.window_mut().set_position(x, y);
+    }
+
+    pub fn set_window_size_limits(&mut self, min: Option<(u32, u32)>, max: Option<(u32, u32)>) {
+        self.ca.window_mut().set_size_limits(min, max);
+    }
+
+    pub fn set_window_icon(&mut self, images: &[RgbaImage]) {
+        self.ca.window_mut().set_icon(images);
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        self.ca.window().mode()
+    }
+
+    /// Changes the window mode, fullscreen modes stay on the monitor that was used last.
+    pub fn set_window_mode(&mut self, mode: WindowMode) {
+        let monitor = self.ca.window().monitor();
+        self.ca.window_mut().set_mode(mode, monitor);
+    }
+
+    /// Changes the window mode, fullscreen modes use the monitor with the given index from [`Commands::monitors`].
+    pub fn set_window_mode_on(&mut self, mode: WindowMode, monitor: usize) {
+        self.ca.window_mut().set_mode(mode, monitor);
+    }
+
+    pub fn monitors(&mut self) -> Vec<Monitor> {
+        self.ca.window_mut().monitors()
+    }
+
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.ca.window_mut().set_vsync(vsync);
+    }
+
+    pub fn minimize_window(&mut self) {
+        self.ca.window_mut().minimize();
+    }
+
+    pub fn maximize_window(&mut self) {
+        self.ca.window_mut().maximize();
+    }
+
+    pub fn restore_window(&mut self) {
+        self.ca.window_mut().restore();
+    }
+
+    pub fn request_attention(&mut self) {
+        self.ca.window_mut().request_attention();
+    }
+
+    pub fn clipboard(&self) -> Option<String> {
+        self.ca.clipboard()
+    }
+
+    pub fn set_clipboard(&mut self, text: &str) {
+        self.ca.set_clipboard(text);
+    }
+
+    pub fn cursor_mode(&self) -> CursorMode {
+        self.ca.cursor_mode()
+    }
+
+    pub fn set_cursor_mode(&mut self, mode: CursorMode) {
+        self.ca.set_cursor_mode(mode);
+    }
+
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.set_cursor_mode(if visible { CursorMode::Normal } else { CursorMode::Hidden });
+    }
+
+    /// Hides the cursor and locks it to the window, optionally using raw (unaccelerated) mouse motion.
+    pub fn lock_cursor(&mut self, raw_motion: bool) {
+        self.set_cursor_mode(CursorMode::Locked);
+        if raw_motion {
+            self.set_raw_mouse_motion(true);
+        }
+    }
+
+    pub fn unlock_cursor(&mut self) {
+        self.set_raw_mouse_motion(false);
+        self.set_cursor_mode(CursorMode::Normal);
+    }
+
+    /// Returns `false` if raw mouse motion is not supported on this platform.
+    pub fn set_raw_mouse_motion(&mut self, enabled: bool) -> bool {
+        self.ca.set_raw_mouse_motion(enabled)
+    }
+
+    pub fn set_cursor(&mut self, cursor: impl Into<Cursor>) {
+        self.ca.set_cursor(&cursor.into());
+    }
+
+    pub fn input(&self) -> &InputState {
+        self.ca.input()
+    }
+
+    pub fn key_pressed(&self, key: Key) -> bool {
+        self.input().key_pressed(key)
+    }
+
+    pub fn key_just_pressed(&self, key: Key) -> bool {
+        self.input().key_just_pressed(key)
+    }
+
+    pub fn key_just_released(&self, key: Key) -> bool {
+        self.input().key_just_released(key)
+    }
+
+    pub fn mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.input().mouse_button_pressed(button)
+    }
+
+    pub fn mouse_button_just_pressed(&self, button: MouseButton) -> bool {
+        self.input().mouse_button_just_pressed(button)
+    }
+
+    pub fn mouse_button_just_released(&self, button: MouseButton) -> bool {
+        self.input().mouse_button_just_released(button)
+    }
+
+    pub fn mouse_position(&self) -> Vec2 {
+        self.input().mouse_position()
+    }
+
+    pub fn mouse_delta(&self) -> Vec2 {
+        self.input().mouse_delta()
+    }
+
+    pub fn mouse_scroll(&self) -> Vec2 {
+        self.input().mouse_scroll()
+    }
+
+    pub fn gamepads(&self) -> Vec<Gamepad> {
+        self.input().gamepads().collect()
+    }
+
+    pub fn gamepad_button_pressed(&self, gamepad: Gamepad, button: GamepadButton) -> bool {
+        self.input().gamepad_button_pressed(gamepad, button)
+    }
+
+    pub fn gamepad_button_just_pressed(&self, gamepad: Gamepad, button: GamepadButton) -> bool {
+        self.input().gamepad_button_just_pressed(gamepad, button)
+    }
+
+    pub fn gamepad_button_just_released(&self, gamepad: Gamepad, button: GamepadButton) -> bool {
+        self.input().gamepad_button_just_released(gamepad, button)
+    }
+
+    pub fn gamepad_axis(&self, gamepad: Gamepad, axis: GamepadAxis) -> f32 {
+        self.input().gamepad_axis(gamepad, axis)
+    }
+
+    pub fn input_map(&self) -> &InputMap {
+        self.ca.input_map()
+    }
+
+    pub fn input_map_mut(&mut self) -> &mut InputMap {
+        self.ca.input_map_mut()
+    }
+
+    pub fn action_pressed(&self, action: &str) -> bool {
+        self.input_map().pressed(action, self.input())
+    }
+
+    pub fn action_just_pressed(&self, action: &str) -> bool {
+        self.input_map().just_pressed(action, self.input())
+    }
+
+    pub fn action_just_released(&self, action: &str) -> bool {
+        self.input_map().just_released(action, self.input())
+    }
+
+    pub fn action_axis(&self, action: &str) -> f32 {
+        self.input_map().axis(action, self.input())
+    }
+
     pub fn add_mesh(&mut self, mesh: Mesh) -> MeshId {
         self.ca.add_mesh(mesh)
     }
 
+    /// Returns the id of an existing mesh with the same contents if there is one, see
+    /// [`Paper::add_mesh_deduplicated`](crate::Paper::add_mesh_deduplicated).
+    pub fn add_mesh_deduplicated(&mut self, mesh: Mesh) -> MeshId {
+        self.ca.add_mesh_deduplicated(mesh)
+    }
+
+    /// Streams new data into an existing mesh, entities using it keep their mesh id.
+    /// Returns `false` if there is no such mesh.
+    pub fn update_mesh(&mut self, mesh_id: MeshId, mesh: Mesh) -> bool {
+        self.ca.update_mesh(mesh_id, mesh)
+    }
+
+    /// Frees a mesh, deferred until no entity uses it anymore. Returns `false` if there is no such mesh.
+    pub fn remove_mesh(&mut self, mesh_id: MeshId) -> bool {
+        self.ca.remove_mesh(mesh_id)
+    }
+
+    /// Frees a material, deferred until no entity uses it anymore. Returns `false` if there is no such material.
+    pub fn remove_material(&mut self, material_id: MaterialId) -> bool {
+        self.ca.remove_material(material_id)
+    }
+
     pub fn add_material<M: Material + 'static>(&mut self, material: M) -> MaterialId {
         self.ca.add_material(Box::new(material))
     }
@@ -67,10 +446,20 @@ impl<'a> Commands<'a> {
         self.ca.add_entity(entity)
     }
 
+    /// Spawns an [`Svg`] as a single entity, see [`Paper::add_svg`](crate::Paper::add_svg).
+    pub fn add_svg(&mut self, svg: &Svg, transform: Transform) -> EntityId {
+        self.ca.add_svg(svg, transform)
+    }
+
     pub fn remove_entity(&mut self, entity_id: EntityId) -> Option<Entity> {
         self.ca.remove_entity(entity_id)
     }
 
+    /// Debug drawing for the current frame, e.g. `cmd.gizmos().line(a, b, RED)`.
+    pub fn gizmos(&mut self) -> &mut Gizmos {
+        self.ca.gizmos()
+    }
+
     pub fn set_material_uniform(&mut self, material_id: MaterialId, name: &str, value: ShaderUniform) {
         self.ca.set_material_uniform(material_id, name, value);
     }
@@ -122,4 +511,14 @@ impl<'a> Commands<'a> {
             transform.scale(scale);
         }
     }
+
+    // ---------------< PRIVATE >---------------
+
+    fn state_stack_mut<S: State>(&mut self) -> Option<&mut StateStack<S>> {
+        let stack = self.resource_mut::<StateStack<S>>();
+        if stack.is_none() {
+            error!("State {} was not added", std::any::type_name::<S>());
+        }
+        stack
+    }
 }
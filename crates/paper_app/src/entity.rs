@@ -2,8 +2,8 @@ use std::ops::{Deref, DerefMut};
 
 use log::error;
 use paper_color::Srgba;
-use paper_math::Transform;
-use paper_render::{Mesh, ShaderUniform, Shape2D};
+use paper_math::{Transform, Vec3};
+use paper_render::{ShaderUniform, Shape2D, Stroke};
 
 use crate::{Paper, PaperApp};
 
@@ -11,16 +11,22 @@ pub const PROJECTION_UNIFORM: &str = "uProjection";
 pub const MODEL_UNIFORM: &str = "uModel";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct MeshId(u64);
+pub struct MeshId(uuid::Uuid);
 
 impl MeshId {
-    pub fn new(mesh: &Mesh) -> Self {
-        MeshId(paper_utils::hash(mesh))
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for MeshId {
+    fn default() -> Self {
+        MeshId(uuid::Uuid::new_v4())
     }
 }
 
 impl Deref for MeshId {
-    type Target = u64;
+    type Target = uuid::Uuid;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -91,18 +97,56 @@ impl DerefMut for EntityId {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Entity {
-    MeshMaterial { mesh_id: MeshId, material_id: MaterialId, transform: Transform },
-    Primitive { shape: Shape2D, color: Srgba },
+    MeshMaterial {
+        mesh_id:     MeshId,
+        material_id: MaterialId,
+        transform:   Transform,
+    },
+    /// A filled shape, optionally stroked with the given stroke and color.
+    Primitive {
+        shape:  Shape2D,
+        color:  Srgba,
+        stroke: Option<(Stroke, Srgba)>,
+    },
+    /// Only the outline of a shape.
+    Outline {
+        shape:  Shape2D,
+        stroke: Stroke,
+        color:  Srgba,
+    },
 }
 
 impl Entity {
+    pub fn primitive(shape: Shape2D, color: Srgba) -> Self {
+        Entity::Primitive { shape, color, stroke: None }
+    }
+
+    pub fn outline(shape: Shape2D, stroke: Stroke, color: Srgba) -> Self {
+        Entity::Outline { shape, stroke, color }
+    }
+
+    /// Adds a stroke to a primitive or replaces the stroke of an outline.
+    pub fn with_stroke(mut self, stroke: Stroke, color: Srgba) -> Self {
+        match &mut self {
+            Entity::Primitive { stroke: current, .. } => *current = Some((stroke, color)),
+            Entity::Outline { stroke: current, color: current_color, .. } => {
+                *current = stroke;
+                *current_color = color;
+            }
+            Entity::MeshMaterial { .. } => error!("Only primitive and outline entities can have a stroke"),
+        }
+        self
+    }
+
     pub(crate) fn transform(&self) -> &Transform {
         match self {
             Entity::MeshMaterial { transform, .. } => transform,
-            Entity::Primitive { .. } => {
-                unreachable!("Primitive entities will be transformed into MeshMaterial entities before rendering")
+            Entity::Primitive { .. } | Entity::Outline { .. } => {
+                unreachable!(
+                    "Primitive and outline entities are transformed into MeshMaterial entities before rendering"
+                )
             }
         }
     }
@@ -110,12 +154,22 @@ impl Entity {
     pub(crate) fn transform_mut(&mut self) -> &mut Transform {
         match self {
             Entity::MeshMaterial { transform, .. } => transform,
-            Entity::Primitive { .. } => {
-                unreachable!("Primitive entities will be transformed into MeshMaterial entities before rendering")
+            Entity::Primitive { .. } | Entity::Outline { .. } => {
+                unreachable!(
+                    "Primitive and outline entities are transformed into MeshMaterial entities before rendering"
+                )
             }
         }
     }
 
+    /// The mesh and material used by the entity.
+    pub(crate) fn gpu_resources(&self) -> Option<(MeshId, MaterialId)> {
+        match self {
+            Entity::MeshMaterial { mesh_id, material_id, .. } => Some((*mesh_id, *material_id)),
+            Entity::Primitive { .. } | Entity::Outline { .. } => None,
+        }
+    }
+
     pub(crate) fn draw<T: PaperApp>(&self, paper: &mut Paper<T>, uniforms: Vec<(String, ShaderUniform)>) {
         match self {
             Entity::MeshMaterial { mesh_id, material_id, transform } => {
@@ -139,8 +193,10 @@ impl Entity {
                 material.bind();
                 mesh.draw();
             }
-            Entity::Primitive { .. } => {
-                unreachable!("Primitive entities will be transformed into MeshMaterial entities before rendering")
+            Entity::Primitive { .. } | Entity::Outline { .. } => {
+                unreachable!(
+                    "Primitive and outline entities are transformed into MeshMaterial entities before rendering"
+                )
             }
         }
     }
@@ -148,9 +204,78 @@ impl Entity {
     pub(crate) fn uniforms(&self) -> Vec<(String, ShaderUniform)> {
         match self {
             Entity::MeshMaterial { .. } => Vec::new(),
-            Entity::Primitive { color, .. } => {
-                vec![("uColor".into(), ShaderUniform::Vec4(color.as_array()))]
-            }
+            Entity::Primitive { color, .. } | Entity::Outline { color, .. } => color_uniforms(*color),
         }
     }
 }
+
+/// The stroke of a primitive entity, drawn right after its fill.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct AttachedStroke {
+    pub mesh_id:     MeshId,
+    pub material_id: MaterialId,
+    pub color:       Srgba,
+    /// Scale of the fill mesh, which the stroke mesh already has applied.
+    pub fill_scale:  Vec3,
+}
+
+impl AttachedStroke {
+    pub(crate) fn draw<T: PaperApp>(&self, paper: &mut Paper<T>, entity_transform: &Transform) {
+        let transform = self.transform(entity_transform);
+        let entity = Entity::MeshMaterial { mesh_id: self.mesh_id, material_id: self.material_id, transform };
+        entity.draw(paper, color_uniforms(self.color));
+    }
+
+    /// The transform of the entity without the scale of the fill mesh. Axes along which the fill is
+    /// flat, e.g. a circle with a radius of zero, keep the scale of the entity instead.
+    pub(crate) fn transform(&self, entity_transform: &Transform) -> Transform {
+        let flat = self.fill_scale.abs().cmple(Vec3::splat(f32::EPSILON));
+        let fill_scale = Vec3::select(flat, Vec3::ONE, self.fill_scale);
+
+        Transform { scale: entity_transform.scale / fill_scale, ..*entity_transform }
+    }
+}
+
+// ---------------< PRIVATE >---------------
+
+fn color_uniforms(color: Srgba) -> Vec<(String, ShaderUniform)> {
+    vec![("uColor".into(), ShaderUniform::Vec4(color.as_array()))]
+}
+
+#[cfg(test)]
+mod tests {
+    use paper_math::Quat;
+
+    use super::*;
+
+    fn stroke(fill_scale: Vec3) -> AttachedStroke {
+        AttachedStroke {
+            mesh_id: MeshId::new(),
+            material_id: MaterialId::new(),
+            color: Srgba::rgb(1.0, 1.0, 1.0),
+            fill_scale,
+        }
+    }
+
+    #[test]
+    fn stroke_transform_removes_fill_scale() {
+        let entity = Transform::new(Vec3::new(1.0, 2.0, 0.0), Quat::from_rotation_z(0.5), Vec3::new(20.0, 5.0, 1.0));
+        let transform = stroke(Vec3::new(10.0, 10.0, 1.0)).transform(&entity);
+
+        assert_eq!(transform.translation, entity.translation);
+        assert_eq!(transform.rotation, entity.rotation);
+        assert_eq!(transform.scale, Vec3::new(2.0, 0.5, 1.0));
+    }
+
+    #[test]
+    fn stroke_transform_of_flat_fill_is_finite() {
+        // A circle with a radius of zero, scaled up through its entity
+        let entity = Transform::IDENTITY.with_scale(Vec3::new(3.0, 3.0, 1.0));
+        let transform = stroke(Vec3::new(0.0, 0.0, 1.0)).transform(&entity);
+        assert_eq!(transform.scale, Vec3::new(3.0, 3.0, 1.0));
+
+        let transform = stroke(Vec3::new(-0.0, 4.0, 1.0)).transform(&entity);
+        assert_eq!(transform.scale, Vec3::new(3.0, 0.75, 1.0));
+        assert!(transform.flatten().iter().all(|value| value.is_finite()));
+    }
+}
@@ -0,0 +1,221 @@
+use std::f32::consts::TAU;
+
+use paper_color::Srgba;
+use paper_math::{UVec2, Vec2};
+use paper_render::{AttributeType, Mesh, Topology, positions_from_vec2s};
+
+const CIRCLE_SEGMENTS: u32 = 32;
+/// Length of arrow heads relative to the arrow.
+const ARROW_HEAD: f32 = 0.2;
+
+/// Immediate mode debug drawing in world space.
+///
+/// Everything drawn is batched into a single line mesh, drawn on top of the scene for one frame and
+/// then cleared. Gizmos are disabled in release builds, where drawing does nothing, unless they are
+/// enabled with [`Gizmos::set_enabled`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gizmos {
+    enabled:    bool,
+    line_width: f32,
+    positions:  Vec<Vec2>,
+    colors:     Vec<f32>,
+}
+
+impl Gizmos {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn set_line_width(&mut self, line_width: f32) {
+        self.line_width = line_width;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn line(&mut self, a: Vec2, b: Vec2, color: Srgba) -> &mut Self {
+        if self.enabled {
+            self.positions.extend([a, b]);
+            self.colors.extend(color.as_array());
+            self.colors.extend(color.as_array());
+        }
+        self
+    }
+
+    /// Connects consecutive points, closing the loop if `closed` is set.
+    pub fn polyline(&mut self, points: &[Vec2], closed: bool, color: Srgba) -> &mut Self {
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], color);
+        }
+        if closed && let (Some(first), Some(last)) = (points.first(), points.last()) {
+            self.line(*last, *first, color);
+        }
+        self
+    }
+
+    pub fn ray(&mut self, origin: Vec2, direction: Vec2, color: Srgba) -> &mut Self {
+        self.line(origin, origin + direction, color)
+    }
+
+    pub fn arrow(&mut self, from: Vec2, to: Vec2, color: Srgba) -> &mut Self {
+        let head = (from - to) * ARROW_HEAD;
+        self.line(from, to, color);
+        self.line(to, to + Vec2::from_angle(0.5).rotate(head), color);
+        self.line(to, to + Vec2::from_angle(-0.5).rotate(head), color)
+    }
+
+    pub fn rect(&mut self, center: Vec2, size: Vec2, color: Srgba) -> &mut Self {
+        let half = size * 0.5;
+        let corners =
+            [center - half, center + Vec2::new(half.x, -half.y), center + half, center + Vec2::new(-half.x, half.y)];
+        self.polyline(&corners, true, color)
+    }
+
+    pub fn circle(&mut self, center: Vec2, radius: f32, color: Srgba) -> &mut Self {
+        if !self.enabled {
+            return self;
+        }
+
+        let points: Vec<Vec2> = (0..CIRCLE_SEGMENTS)
+            .map(|i| center + Vec2::from_angle(i as f32 / CIRCLE_SEGMENTS as f32 * TAU) * radius)
+            .collect();
+        self.polyline(&points, true, color)
+    }
+
+    /// A grid of `cells` cells of `cell_size`, centered on `center`.
+    pub fn grid(&mut self, center: Vec2, cell_size: Vec2, cells: UVec2, color: Srgba) -> &mut Self {
+        let size = cell_size * cells.as_vec2();
+        let min = center - size * 0.5;
+
+        for x in 0..=cells.x {
+            let x = min.x + x as f32 * cell_size.x;
+            self.line(Vec2::new(x, min.y), Vec2::new(x, min.y + size.y), color);
+        }
+        for y in 0..=cells.y {
+            let y = min.y + y as f32 * cell_size.y;
+            self.line(Vec2::new(min.x, y), Vec2::new(min.x + size.x, y), color);
+        }
+        self
+    }
+
+    /// An axis aligned cross with arms of `size`.
+    pub fn cross(&mut self, center: Vec2, size: f32, color: Srgba) -> &mut Self {
+        self.line(center - Vec2::new(size, 0.0), center + Vec2::new(size, 0.0), color);
+        self.line(center - Vec2::new(0.0, size), center + Vec2::new(0.0, size), color)
+    }
+
+    pub(crate) fn mesh(&self) -> Mesh {
+        Mesh::new()
+            .with_attribute(AttributeType::Position, positions_from_vec2s(&self.positions))
+            .with_attribute(AttributeType::Color, self.colors.clone())
+            .with_topology(Topology::Lines)
+            .with_line_width(self.line_width)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.positions.clear();
+        self.colors.clear();
+    }
+}
+
+impl Default for Gizmos {
+    fn default() -> Self {
+        Self { enabled: cfg!(debug_assertions), line_width: 1.0, positions: Vec::new(), colors: Vec::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use paper_color::{RED, WHITE};
+
+    use super::*;
+
+    fn enabled() -> Gizmos {
+        let mut gizmos = Gizmos::default();
+        gizmos.set_enabled(true);
+        gizmos
+    }
+
+    fn lines(gizmos: &Gizmos) -> usize {
+        gizmos.positions.len() / 2
+    }
+
+    #[test]
+    fn disabled_in_release_builds() {
+        assert_eq!(Gizmos::default().is_enabled(), cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn drawing_does_nothing_while_disabled() {
+        let mut gizmos = enabled();
+        gizmos.set_enabled(false);
+
+        gizmos.line(Vec2::ZERO, Vec2::ONE, RED).circle(Vec2::ZERO, 1.0, RED).grid(
+            Vec2::ZERO,
+            Vec2::ONE,
+            UVec2::ONE,
+            RED,
+        );
+        gizmos.arrow(Vec2::ZERO, Vec2::X, RED).rect(Vec2::ZERO, Vec2::ONE, RED).cross(Vec2::ZERO, 1.0, RED);
+        assert!(gizmos.is_empty());
+        assert!(gizmos.colors.is_empty());
+    }
+
+    #[test]
+    fn segment_counts() {
+        let count = |draw: &dyn Fn(&mut Gizmos)| {
+            let mut gizmos = enabled();
+            draw(&mut gizmos);
+            lines(&gizmos)
+        };
+
+        assert_eq!(count(&|g| _ = g.line(Vec2::ZERO, Vec2::X, RED)), 1);
+        assert_eq!(count(&|g| _ = g.arrow(Vec2::ZERO, Vec2::X, RED)), 3);
+        assert_eq!(count(&|g| _ = g.circle(Vec2::ZERO, 2.0, RED)), CIRCLE_SEGMENTS as usize);
+        assert_eq!(count(&|g| _ = g.grid(Vec2::ZERO, Vec2::ONE, UVec2::new(3, 2), RED)), 4 + 3);
+        assert_eq!(count(&|g| _ = g.rect(Vec2::ZERO, Vec2::ONE, RED)), 4);
+        assert_eq!(count(&|g| _ = g.cross(Vec2::ZERO, 1.0, RED)), 2);
+        assert_eq!(count(&|g| _ = g.polyline(&[Vec2::ZERO, Vec2::X, Vec2::ONE], false, RED)), 2);
+        assert_eq!(count(&|g| _ = g.polyline(&[Vec2::ZERO, Vec2::X, Vec2::ONE], true, RED)), 3);
+        assert_eq!(count(&|g| _ = g.polyline(&[], true, RED)), 0);
+    }
+
+    #[test]
+    fn shapes_are_placed_in_world_space() {
+        let mut gizmos = enabled();
+        gizmos.circle(Vec2::new(5.0, 0.0), 2.0, RED);
+        assert!(gizmos.positions.iter().all(|p| (p.distance(Vec2::new(5.0, 0.0)) - 2.0).abs() < 1e-5));
+
+        let mut gizmos = enabled();
+        gizmos.grid(Vec2::new(1.0, 1.0), Vec2::new(2.0, 1.0), UVec2::new(2, 4), RED);
+        let bounds = gizmos.positions.iter().fold((Vec2::MAX, Vec2::MIN), |(min, max), p| (min.min(*p), max.max(*p)));
+        assert_eq!(bounds, (Vec2::new(-1.0, -1.0), Vec2::new(3.0, 3.0)));
+
+        // Arrow heads point back from the tip
+        let mut gizmos = enabled();
+        gizmos.arrow(Vec2::ZERO, Vec2::new(10.0, 0.0), RED);
+        for head in gizmos.positions[2..].chunks(2) {
+            assert_eq!(head[0], Vec2::new(10.0, 0.0));
+            assert!((head[1].distance(head[0]) - 2.0).abs() < 1e-5 && head[1].x < 10.0);
+        }
+    }
+
+    #[test]
+    fn colors_follow_the_vertices_until_cleared() {
+        let mut gizmos = enabled();
+        gizmos.line(Vec2::ZERO, Vec2::X, RED).line(Vec2::ZERO, Vec2::Y, WHITE);
+
+        assert_eq!(gizmos.colors.len(), gizmos.positions.len() * 4);
+        assert_eq!(gizmos.colors[..8], [RED.as_array(), RED.as_array()].concat());
+        assert_eq!(gizmos.colors[8..], [WHITE.as_array(), WHITE.as_array()].concat());
+
+        gizmos.clear();
+        assert!(gizmos.is_empty() && gizmos.colors.is_empty());
+        assert!(gizmos.is_enabled());
+    }
+}
@@ -2,13 +2,28 @@ mod app;
 mod camera;
 mod commands;
 mod entity;
+mod gizmos;
 mod paper;
+mod plugin;
+mod replay;
+mod resource;
+mod state;
+mod system;
+mod usage;
+mod user_event;
 
 pub use app::*;
 pub use camera::*;
 pub use commands::*;
 pub use entity::*;
+pub use gizmos::*;
 pub use paper::*;
+pub use plugin::*;
+pub use replay::*;
+pub use resource::*;
+pub use state::*;
+pub use system::*;
+pub use user_event::*;
 
 pub mod prelude {
     pub use crate::{
@@ -16,6 +31,13 @@ pub mod prelude {
         camera::Camera2D,
         commands::Commands,
         entity::{Entity, EntityId, MaterialId, MeshId},
+        gizmos::Gizmos,
         paper::Paper,
+        plugin::Plugin,
+        replay::Recording,
+        resource::Resources,
+        state::{State, StateStack, in_state},
+        system::{Stage, System},
+        user_event::UserEvents,
     };
 }
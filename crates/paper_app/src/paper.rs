@@ -1,47 +1,81 @@
-use std::fmt::Debug;
+use std::{any::TypeId, fmt::Debug, path::Path};
 
 use glfw::Context;
-use hashbrown::HashMap;
-use log::{debug, error, info};
+use hashbrown::{HashMap, HashSet};
+use log::{debug, error, info, warn};
 use paper_color::DEEP_BLUE;
-use paper_input::Event;
+use paper_input::{Event, EventKind, GamepadSource, GlfwGamepadSource, InputMap, InputState};
 use paper_math::{Transform, Vec2};
-use paper_render::{ColorMaterial, InternalMesh, Material, Mesh, Shader, ShaderUniform};
+use paper_render::{
+    ColorMaterial, DefaultMaterial, GpuObjects, InternalMesh, Material, Mesh, Shader, ShaderUniform, Svg,
+};
 use paper_utils::default;
-use paper_window::{Window, prelude::WindowConfig};
+use paper_window::{Cursor, CursorMode, Window, prelude::WindowConfig};
 
 use crate::{
-    Camera2D, EmptyApp, Entity, EntityId, MaterialId, MeshId, PaperApp,
+    Camera2D, EmptyApp, Entity, EntityId, Gizmos, MODEL_UNIFORM, MaterialId, MeshId, PROJECTION_UNIFORM, PaperApp,
+    Plugin, Recording, Resources, Stage, State, StateStack, System, SystemFn, UserEvents,
     commands::{Commandable, Commands},
+    entity::AttachedStroke,
+    in_state,
+    replay::Session,
+    state::{SpawnedEntity, StateContext, StateDriver, StateHooks},
+    system::Schedule,
+    usage::Usage,
 };
 
 const FIXED_TIME_STEP: f64 = 1.0 / 24.0;
 
 pub type EventCallback<T> = Box<dyn Fn(Commands, &mut T)>;
 
-pub struct Paper<T: PaperApp = EmptyApp> {
-    window: Window,
+pub(crate) type Uniforms = Vec<(String, ShaderUniform)>;
 
+pub struct Paper<T: PaperApp = EmptyApp> {
     max_fps:    Option<f64>,
     delta_time: f64,
 
     fixed_time_step:  f64,
     fixed_delta_time: f64,
 
-    mouse_pos: Vec2,
+    input:     InputState,
+    input_map: InputMap,
+
+    gamepad_source: Box<dyn GamepadSource>,
+    session:        Session,
+
+    plugins:   HashSet<String>,
+    schedule:  Schedule<T>,
+    resources: Resources,
+
+    state_drivers:    HashMap<TypeId, Box<dyn StateDriver<T>>>,
+    user_events:      HashMap<TypeId, fn(&mut Resources)>,
+    spawned_entities: Option<Vec<SpawnedEntity>>,
+    spawn_owner:      Option<TypeId>,
 
     pub(crate) camera: Camera2D,
 
     triggered_events: Vec<Event>,
-    event_callbacks:  HashMap<Event, Vec<EventCallback<T>>>,
+    event_callbacks:  HashMap<EventKind, Vec<EventCallback<T>>>,
+    action_callbacks: HashMap<String, Vec<EventCallback<T>>>,
     current_events:   Vec<Event>,
 
     entities_map: HashMap<EntityId, usize>,
-    entities:     Vec<(EntityId, Entity, Vec<(String, ShaderUniform)>)>,
+    entities:     Vec<(EntityId, Entity, Uniforms)>,
+    strokes:      HashMap<EntityId, AttachedStroke>,
 
     pub(crate) meshes:    HashMap<MeshId, InternalMesh>,
     pub(crate) materials: HashMap<MaterialId, Box<dyn Material>>,
     // material_name_map:    HashMap<String, MaterialId>,
+    mesh_hashes:          HashMap<(MeshOwner, u64), Vec<MeshId>>,
+    hashed_meshes:        HashMap<MeshId, (MeshOwner, u64)>,
+    mesh_usage:           Usage<MeshId>,
+    material_usage:       Usage<MaterialId>,
+
+    gizmos:      Gizmos,
+    gizmo_batch: Option<(InternalMesh, DefaultMaterial)>,
+
+    // Declared last so meshes and materials are dropped while the GL context still exists
+    window: Window,
 }
 
 impl<T: PaperApp> Paper<T> {
@@ -57,30 +91,55 @@ impl<T: PaperApp> Paper<T> {
             std::process::exit(1);
         };
 
+        let gamepad_source = Box::new(GlfwGamepadSource::new(window.glfw.clone()));
+        let (width, height) = window.logical_size();
+
         info!("Paper application initialized");
 
         Self {
-            window,
-
             max_fps: None,
             delta_time: 0.0,
 
             fixed_time_step: FIXED_TIME_STEP,
             fixed_delta_time: 0.0,
 
-            mouse_pos: Vec2::ZERO,
+            input: InputState::new(),
+            input_map: InputMap::new(),
+
+            gamepad_source,
+            session: Session::Live,
 
-            camera: Camera2D { viewport: Vec2::new(config.width as f32, config.height as f32), ..default() },
+            plugins: HashSet::new(),
+            schedule: Schedule::new(),
+            resources: Resources::new(),
+
+            state_drivers: HashMap::new(),
+            user_events: HashMap::new(),
+            spawned_entities: None,
+            spawn_owner: None,
+
+            camera: Camera2D { viewport: Vec2::new(width, height), ..default() },
 
             triggered_events: Vec::new(),
             event_callbacks: HashMap::new(),
+            action_callbacks: HashMap::new(),
             current_events: Vec::new(),
 
             entities_map: HashMap::new(),
             entities: Vec::new(),
+            strokes: HashMap::new(),
 
             meshes: HashMap::new(),
             materials: HashMap::new(),
+            mesh_hashes: HashMap::new(),
+            hashed_meshes: HashMap::new(),
+            mesh_usage: Usage::new(),
+            material_usage: Usage::new(),
+
+            gizmos: Gizmos::default(),
+            gizmo_batch: None,
+
+            window,
         }
     }
 
@@ -88,6 +147,7 @@ impl<T: PaperApp> Paper<T> {
         let mut app = T::new(Commands::new(self));
 
         app.setup(Commands::new(self));
+        self.apply_state_transitions(&mut app);
 
         let frame_time = self.max_fps.map(|fps| 1.0 / fps);
         let mut last_frame = std::time::Instant::now();
@@ -98,18 +158,27 @@ impl<T: PaperApp> Paper<T> {
 
         while !self.window.p_window.should_close() {
             let now = std::time::Instant::now();
-            delta_time += now.duration_since(last_frame).as_secs_f64();
-            fixed_delta_time += now.duration_since(last_frame).as_secs_f64();
+            let elapsed = now.duration_since(last_frame).as_secs_f64();
             last_frame = now;
 
+            let Some(elapsed) = self.session.begin_frame(elapsed) else {
+                info!("Replay finished");
+                break;
+            };
+            delta_time += elapsed;
+            fixed_delta_time += elapsed;
+
             self.window.glfw.poll_events();
             let mut events = self.events(&mut app);
             self.current_events.append(&mut events);
 
+            self.apply_state_transitions(&mut app);
+
             if fixed_delta_time >= self.fixed_time_step {
                 self.fixed_delta_time = fixed_delta_time;
 
                 app.fixed_update(Commands::new(self));
+                self.run_systems(Stage::Fixed, &mut app);
 
                 fixed_delta_time -= self.fixed_time_step;
             }
@@ -126,22 +195,173 @@ impl<T: PaperApp> Paper<T> {
                 delta_time = 0.0;
             }
 
+            self.run_systems(Stage::PreUpdate, &mut app);
             app.update(Commands::new(self));
+            self.run_systems(Stage::Update, &mut app);
+            self.run_systems(Stage::PostUpdate, &mut app);
 
+            self.run_systems(Stage::PreRender, &mut app);
             self.render();
 
+            self.gizmos.clear();
             self.current_events.clear();
-            self.triggered_events.clear();
+            self.input.end_frame();
+            self.update_user_events();
         }
 
         app.cleanup(Commands::new(self));
+        self.session.finish();
+        self.release_gpu_resources();
+    }
+
+    /// Records the input and frame times of the session to `path`, the file is written when the
+    /// application exits. See [`Paper::replay`].
+    pub fn record(&mut self, path: impl AsRef<Path>) {
+        self.session = Session::record(path);
+    }
+
+    pub fn with_recording(mut self, path: impl AsRef<Path>) -> Self {
+        self.record(path);
+        self
+    }
+
+    /// Replays a [`Recording`] instead of reading input from the window. Every frame uses the recorded
+    /// delta time, so the session is reproduced as long as the application itself is deterministic.
+    /// The application exits once all frames have been replayed.
+    ///
+    /// Window size and position are not replayed, only the events reporting them. Replays still open a
+    /// window, use [`InputReplay`](crate::InputReplay) to check input handling without a display.
+    pub fn replay(&mut self, recording: Recording) {
+        self.session = Session::replay(recording);
+    }
+
+    pub fn with_replay(mut self, recording: Recording) -> Self {
+        self.replay(recording);
+        self
+    }
+
+    pub fn add_plugin<P: Plugin<T>>(&mut self, plugin: P) {
+        let name = plugin.name().to_string();
+        if plugin.is_unique() && !self.plugins.insert(name.clone()) {
+            warn!("Plugin '{name}' was already added");
+            return;
+        }
+
+        debug!("Building plugin '{name}'");
+        plugin.build(self);
+    }
+
+    pub fn with_plugin<P: Plugin<T>>(mut self, plugin: P) -> Self {
+        self.add_plugin(plugin);
+        self
+    }
+
+    pub fn has_plugin(&self, name: &str) -> bool {
+        self.plugins.contains(name)
+    }
+
+    pub fn add_system(&mut self, stage: Stage, system: impl Into<System<T>>) {
+        self.schedule.add(stage, system.into());
+    }
+
+    pub fn with_system(mut self, stage: Stage, system: impl Into<System<T>>) -> Self {
+        self.add_system(stage, system);
+        self
+    }
+
+    pub fn insert_resource<R: 'static>(&mut self, resource: R) {
+        self.resources.insert(resource);
+    }
+
+    pub fn with_resource<R: 'static>(mut self, resource: R) -> Self {
+        self.insert_resource(resource);
+        self
+    }
+
+    /// Inserts the default value of a resource unless it already exists.
+    pub fn init_resource<R: Default + 'static>(&mut self) {
+        self.resources.get_or_insert_with(R::default);
+    }
+
+    pub fn resource<R: 'static>(&self) -> Option<&R> {
+        self.resources.get()
+    }
+
+    pub fn resource_mut<R: 'static>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut()
+    }
+
+    /// Registers the user event type `E`, this also happens when the first event is sent.
+    pub fn add_user_event<E: 'static>(&mut self) {
+        self.resources.get_or_insert_with(UserEvents::<E>::default);
+        self.user_events.insert(TypeId::of::<E>(), UserEvents::<E>::update_resource);
+    }
+
+    /// Adds a state machine for states of type `S`, starting in `initial`.
+    ///
+    /// The active states are stored in the [`StateStack<S>`] resource and changed through
+    /// [`Commands::set_state`], [`Commands::push_state`] and [`Commands::pop_state`].
+    pub fn add_state<S: State>(&mut self, initial: S)
+    where
+        T: 'static,
+    {
+        if self.state_drivers.contains_key(&TypeId::of::<S>()) {
+            warn!("State {} was already added", std::any::type_name::<S>());
+            return;
+        }
+
+        self.insert_resource(StateStack::new(initial));
+        self.state_drivers.insert(TypeId::of::<S>(), Box::new(StateHooks::<S, Self>::new()));
+        self.spawned_entities.get_or_insert_default();
+    }
+
+    pub fn with_state<S: State>(mut self, initial: S) -> Self
+    where
+        T: 'static,
+    {
+        self.add_state(initial);
+        self
+    }
+
+    pub fn on_enter<S: State, F: FnMut(Commands, &mut T) + 'static>(&mut self, state: S, hook: F)
+    where
+        T: 'static,
+    {
+        if let Some(hooks) = self.state_hooks::<S>() {
+            hooks.add_enter_hook(state, Box::new(hook));
+        }
+    }
+
+    pub fn on_exit<S: State, F: FnMut(Commands, &mut T) + 'static>(&mut self, state: S, hook: F)
+    where
+        T: 'static,
+    {
+        if let Some(hooks) = self.state_hooks::<S>() {
+            hooks.add_exit_hook(state, Box::new(hook));
+        }
+    }
+
+    /// Removes all entities spawned while `state` was the current state when it is exited. Entities spawned
+    /// by the enter and exit hooks of other state types are not included.
+    pub fn despawn_on_exit<S: State>(&mut self, state: S)
+    where
+        T: 'static,
+    {
+        if let Some(hooks) = self.state_hooks::<S>() {
+            hooks.set_scoped(state, true);
+        }
+    }
+
+    /// Adds a system that only runs while `state` is the current state.
+    pub fn add_state_system<S: State>(&mut self, stage: Stage, state: S, system: impl Into<System<T>>) {
+        self.add_system(stage, system.into().run_if(in_state(state)));
     }
 
     pub fn add_event_callback<F: Fn(Commands, &mut T) + Clone + 'static>(&mut self, event: Event, callback: F) {
         match event {
             Event::AnyKey(keys, action) => {
                 for key in keys {
-                    self.add_event_callback(Event::Key(key, action), callback.clone());
+                    self.add_event_callback(Event::key(key, action), callback.clone());
                 }
                 return;
             }
@@ -153,8 +373,9 @@ impl<T: PaperApp> Paper<T> {
             }
             _ => {}
         }
-        debug!("Adding event callback for {event:?}");
-        self.event_callbacks.entry(event).or_default().push(Box::new(callback));
+        if let Some(kind) = event.kind() {
+            self.add_event_kind_callback(kind, callback);
+        }
     }
 
     pub fn with_event_callback<F: Fn(Commands, &mut T) + Clone + 'static>(mut self, event: Event, callback: F) -> Self {
@@ -162,6 +383,68 @@ impl<T: PaperApp> Paper<T> {
         self
     }
 
+    /// Calls `callback` for every event of the given kind, e.g. `EventKind::MouseScroll` for any scroll.
+    pub fn add_event_kind_callback<F: Fn(Commands, &mut T) + 'static>(&mut self, kind: EventKind, callback: F) {
+        debug!("Adding event callback for {kind:?}");
+        self.event_callbacks.entry(kind).or_default().push(Box::new(callback));
+    }
+
+    pub fn with_event_kind_callback<F: Fn(Commands, &mut T) + 'static>(mut self, kind: EventKind, callback: F) -> Self {
+        self.add_event_kind_callback(kind, callback);
+        self
+    }
+
+    /// Calls `callback` whenever `action` of the [`InputMap`] is just pressed.
+    pub fn add_action_callback<F: Fn(Commands, &mut T) + 'static>(&mut self, action: &str, callback: F) {
+        debug!("Adding action callback for '{action}'");
+        self.action_callbacks.entry(action.to_string()).or_default().push(Box::new(callback));
+    }
+
+    pub fn with_action_callback<F: Fn(Commands, &mut T) + 'static>(mut self, action: &str, callback: F) -> Self {
+        self.add_action_callback(action, callback);
+        self
+    }
+
+    pub fn input_map(&self) -> &InputMap {
+        &self.input_map
+    }
+
+    pub fn input_map_mut(&mut self) -> &mut InputMap {
+        &mut self.input_map
+    }
+
+    pub fn set_input_map(&mut self, input_map: InputMap) {
+        self.input_map = input_map;
+    }
+
+    pub fn with_input_map(mut self, input_map: InputMap) -> Self {
+        self.set_input_map(input_map);
+        self
+    }
+
+    /// Replaces the source gamepads are read from, e.g. with a [`paper_input::VirtualGamepadSource`].
+    pub fn set_gamepad_source<S: GamepadSource + 'static>(&mut self, source: S) {
+        self.gamepad_source = Box::new(source);
+    }
+
+    pub fn with_gamepad_source<S: GamepadSource + 'static>(mut self, source: S) -> Self {
+        self.set_gamepad_source(source);
+        self
+    }
+
+    /// Adds SDL `gamecontrollerdb.txt` style gamepad mappings.
+    pub fn add_gamepad_mappings(&mut self, mappings: &str) -> bool {
+        let added = self.gamepad_source.add_mappings(mappings);
+        if !added {
+            error!("Failed to add gamepad mappings");
+        }
+        added
+    }
+
+    pub fn set_gamepad_deadzone(&mut self, deadzone: f32) {
+        self.input.set_gamepad_deadzone(deadzone);
+    }
+
     pub fn set_camera(&mut self, camera: Camera2D) {
         self.camera = camera;
     }
@@ -172,17 +455,66 @@ impl<T: PaperApp> Paper<T> {
     }
 
     pub fn add_mesh(&mut self, mesh: Mesh) -> MeshId {
-        let mesh_id = MeshId::new(&mesh);
-        if self.meshes.contains_key(&mesh_id) {
-            debug!("Mesh with ID {mesh_id:?} already exists, returning existing mesh ID");
-            return mesh_id;
-        }
+        let mesh_id = MeshId::new();
         let internal_mesh = InternalMesh::build(mesh);
         self.meshes.insert(mesh_id, internal_mesh);
         debug!("Added mesh with ID: {mesh_id:?}");
         mesh_id
     }
 
+    /// Like [`Paper::add_mesh`], but returns the id of an existing mesh with the same contents if
+    /// there is one. Only meshes added through this method are considered, the meshes generated for
+    /// primitive entities are shared among those entities only.
+    pub fn add_mesh_deduplicated(&mut self, mesh: Mesh) -> MeshId {
+        let (mesh_id, created) = self.insert_mesh_deduplicated(mesh, MeshOwner::User);
+        if !created {
+            self.mesh_usage.cancel_removal(&mesh_id);
+        }
+        mesh_id
+    }
+
+    /// Frees a mesh. If entities still use it, it is freed once the last of them is removed.
+    /// Returns `false` if there is no such mesh.
+    pub fn remove_mesh(&mut self, mesh_id: MeshId) -> bool {
+        if !self.meshes.contains_key(&mesh_id) {
+            error!("Failed to remove mesh with ID: {mesh_id:?} (not found)");
+            return false;
+        }
+        if self.mesh_usage.request_removal(mesh_id) {
+            self.free_mesh(mesh_id);
+        } else {
+            debug!("Mesh with ID {mesh_id:?} is still in use, removing it with its last entity");
+        }
+        true
+    }
+
+    /// Frees a material. If entities still use it, it is freed once the last of them is removed.
+    /// Returns `false` if there is no such material.
+    pub fn remove_material(&mut self, material_id: MaterialId) -> bool {
+        if !self.materials.contains_key(&material_id) {
+            error!("Failed to remove material with ID: {material_id:?} (not found)");
+            return false;
+        }
+        if self.material_usage.request_removal(material_id) {
+            self.materials.remove(&material_id);
+            debug!("Removed material with ID: {material_id:?}");
+        } else {
+            debug!("Material with ID {material_id:?} is still in use, removing it with its last entity");
+        }
+        true
+    }
+
+    /// Replaces the data of an existing mesh, its id stays valid. Returns `false` if there is no such mesh.
+    pub fn update_mesh(&mut self, mesh_id: MeshId, mesh: Mesh) -> bool {
+        let Some(internal_mesh) = self.meshes.get_mut(&mesh_id) else {
+            error!("Failed to update mesh with ID: {mesh_id:?} (not found)");
+            return false;
+        };
+        internal_mesh.update(mesh);
+        self.forget_mesh_hash(mesh_id);
+        true
+    }
+
     pub fn add_material<M: Material + 'static>(&mut self, mut material: M) -> MaterialId {
         let material_id = MaterialId::new();
         if material.shaders_from_source() {
@@ -208,12 +540,28 @@ impl<T: PaperApp> Paper<T> {
         let id = EntityId::new();
         let uniforms = entity.uniforms();
 
-        if let Entity::Primitive { shape, .. } = entity {
-            let mesh = shape.mesh();
-            let mesh_id = self.add_mesh(mesh);
-            let material_id = self.add_material(ColorMaterial::default());
-            entity = Entity::MeshMaterial { mesh_id, material_id, transform: shape.transform() };
-            debug!("Transformed primitive entity into MeshMaterial");
+        match &entity {
+            Entity::Primitive { shape, stroke, .. } => {
+                if let Some((stroke, color)) = stroke {
+                    let (mesh_id, material_id) = self.add_primitive_resources(shape.outline_mesh(stroke));
+                    let fill_scale = shape.transform().scale;
+                    self.strokes.insert(id, AttachedStroke { mesh_id, material_id, color: *color, fill_scale });
+                }
+
+                let (mesh_id, material_id) = self.add_primitive_resources(shape.mesh());
+                entity = Entity::MeshMaterial { mesh_id, material_id, transform: shape.transform() };
+                debug!("Transformed primitive entity into MeshMaterial");
+            }
+            Entity::Outline { shape, stroke, .. } => {
+                let (mesh_id, material_id) = self.add_primitive_resources(shape.outline_mesh(stroke));
+                entity = Entity::MeshMaterial { mesh_id, material_id, transform: shape.outline_transform() };
+                debug!("Transformed outline entity into MeshMaterial");
+            }
+            Entity::MeshMaterial { .. } => self.acquire_gpu_resources(&entity),
+        }
+
+        if let Some(spawned) = &mut self.spawned_entities {
+            spawned.push((id, self.spawn_owner));
         }
 
         self.entities.push((id, entity, uniforms));
@@ -222,6 +570,23 @@ impl<T: PaperApp> Paper<T> {
         id
     }
 
+    /// Spawns an [`Svg`] as a single entity drawn with its vertex colors. The generated mesh and
+    /// material belong to the entity and are freed with it.
+    pub fn add_svg(&mut self, svg: &Svg, transform: Transform) -> EntityId {
+        let mesh_id = self.add_mesh(svg.mesh());
+        let material_id = self.add_material(DefaultMaterial::default());
+        let id = self.add_entity(Entity::MeshMaterial { mesh_id, material_id, transform });
+
+        self.mesh_usage.request_removal(mesh_id);
+        self.material_usage.request_removal(material_id);
+        id
+    }
+
+    /// Debug drawing for the current frame, see [`Gizmos`].
+    pub fn gizmos(&mut self) -> &mut Gizmos {
+        &mut self.gizmos
+    }
+
     pub fn get_entity(&self, id: &EntityId) -> Option<&Entity> {
         self.entities_map.get(id).map(|index| &self.entities[*index].1)
     }
@@ -236,6 +601,13 @@ impl<T: PaperApp> Paper<T> {
 
         let (_, entity, _) = self.entities.remove(index);
 
+        if let Some((mesh_id, material_id)) = entity.gpu_resources() {
+            self.release_gpu_resource_pair(mesh_id, material_id);
+        }
+        if let Some(stroke) = self.strokes.remove(&id) {
+            self.release_gpu_resource_pair(stroke.mesh_id, stroke.material_id);
+        }
+
         for i in index..self.entities.len() {
             let id = self.entities[i].0;
             *self.entities_map.get_mut(&id).unwrap() = i;
@@ -255,58 +627,289 @@ impl<T: PaperApp> Paper<T> {
 
     // ---------------< PRIVATE >---------------
 
+    /// Returns the id of the mesh and whether it was newly created.
+    fn insert_mesh_deduplicated(&mut self, mesh: Mesh, owner: MeshOwner) -> (MeshId, bool) {
+        let key = (owner, paper_utils::hash(&mesh));
+
+        // Equal hashes do not guarantee equal meshes, so the contents are compared as well
+        let existing = self.mesh_hashes.get(&key).and_then(|ids| {
+            ids.iter().copied().find(|id| self.meshes.get(id).is_some_and(|internal| internal.mesh == mesh))
+        });
+        if let Some(mesh_id) = existing {
+            debug!("Mesh with ID {mesh_id:?} has the same contents, returning existing mesh ID");
+            return (mesh_id, false);
+        }
+
+        let mesh_id = self.add_mesh(mesh);
+        self.mesh_hashes.entry(key).or_default().push(mesh_id);
+        self.hashed_meshes.insert(mesh_id, key);
+        (mesh_id, true)
+    }
+
+    fn forget_mesh_hash(&mut self, mesh_id: MeshId) {
+        let Some(key) = self.hashed_meshes.remove(&mesh_id) else {
+            return;
+        };
+        if let Some(ids) = self.mesh_hashes.get_mut(&key) {
+            ids.retain(|id| *id != mesh_id);
+            if ids.is_empty() {
+                self.mesh_hashes.remove(&key);
+            }
+        }
+    }
+
+    fn free_mesh(&mut self, mesh_id: MeshId) {
+        self.forget_mesh_hash(mesh_id);
+        self.meshes.remove(&mesh_id);
+        debug!("Removed mesh with ID: {mesh_id:?}");
+    }
+
+    fn acquire_gpu_resources(&mut self, entity: &Entity) {
+        if let Some((mesh_id, material_id)) = entity.gpu_resources() {
+            self.mesh_usage.acquire(mesh_id);
+            self.material_usage.acquire(material_id);
+        }
+    }
+
+    /// Adds the mesh and material generated for a primitive or outline, which belong to the entity
+    /// and are freed with it.
+    fn add_primitive_resources(&mut self, mesh: Mesh) -> (MeshId, MaterialId) {
+        // Primitives of the same shape share their unit mesh
+        let (mesh_id, created) = self.insert_mesh_deduplicated(mesh, MeshOwner::Primitive);
+        let material_id = self.add_material(ColorMaterial::default());
+
+        self.mesh_usage.acquire(mesh_id);
+        self.material_usage.acquire(material_id);
+        if created {
+            self.mesh_usage.request_removal(mesh_id);
+        }
+        self.material_usage.request_removal(material_id);
+
+        (mesh_id, material_id)
+    }
+
+    fn release_gpu_resource_pair(&mut self, mesh_id: MeshId, material_id: MaterialId) {
+        if self.mesh_usage.release(mesh_id) {
+            self.free_mesh(mesh_id);
+        }
+        if self.material_usage.release(material_id) {
+            self.materials.remove(&material_id);
+            debug!("Removed material with ID: {material_id:?}");
+        }
+    }
+
+    /// Drops all meshes and materials and reports GPU objects that are still alive, which were leaked.
+    fn release_gpu_resources(&mut self) {
+        self.entities.clear();
+        self.entities_map.clear();
+        self.strokes.clear();
+        self.gizmo_batch = None;
+        self.meshes.clear();
+        self.mesh_hashes.clear();
+        self.hashed_meshes.clear();
+        self.materials.clear();
+
+        let live = GpuObjects::live();
+        if live.is_empty() {
+            debug!("All GPU objects were released");
+        } else {
+            warn!("GPU objects alive at shutdown: {live}");
+        }
+    }
+
+    fn state_hooks<S: State>(&mut self) -> Option<&mut StateHooks<S, Self>>
+    where
+        T: 'static,
+    {
+        let hooks = self
+            .state_drivers
+            .get_mut(&TypeId::of::<S>())
+            .and_then(|driver| driver.as_any_mut().downcast_mut::<StateHooks<S, Self>>());
+        if hooks.is_none() {
+            error!("State {} was not added", std::any::type_name::<S>());
+        }
+        hooks
+    }
+
+    fn update_user_events(&mut self) {
+        for update in self.user_events.values() {
+            update(&mut self.resources);
+        }
+    }
+
+    fn apply_state_transitions(&mut self, app: &mut T) {
+        if self.state_drivers.is_empty() {
+            return;
+        }
+
+        let mut drivers = std::mem::take(&mut self.state_drivers);
+
+        for driver in drivers.values_mut() {
+            driver.apply_transitions(self, app);
+        }
+
+        let spawned = self.spawned_entities.as_mut().map(std::mem::take).unwrap_or_default();
+        for driver in drivers.values_mut() {
+            driver.finish_tracking(&spawned);
+        }
+
+        self.state_drivers = drivers;
+    }
+
+    fn run_systems(&mut self, stage: Stage, app: &mut T) {
+        let mut systems = self.schedule.take(stage);
+
+        for system in &mut systems {
+            if self.schedule.should_run(system) && system.conditions_met(&Commands::new(self)) {
+                system.run(Commands::new(self), app);
+            }
+        }
+
+        self.schedule.restore(stage, systems);
+    }
+
     fn render(&mut self) {
         self.window.clear();
 
         let temp_entities = std::mem::take(&mut self.entities);
+        let temp_strokes = std::mem::take(&mut self.strokes);
 
-        for (_, entity, uniforms) in &temp_entities {
+        for (id, entity, uniforms) in &temp_entities {
             entity.draw(self, uniforms.clone());
+            if let Some(stroke) = temp_strokes.get(id) {
+                stroke.draw(self, entity.transform());
+            }
         }
 
         self.entities = temp_entities;
+        self.strokes = temp_strokes;
+
+        self.draw_gizmos();
 
         self.window.p_window.swap_buffers();
     }
 
+    /// Streams this frame's gizmos into their line mesh and draws it on top of the scene.
+    fn draw_gizmos(&mut self) {
+        if self.gizmos.is_empty() {
+            return;
+        }
+
+        let mesh = self.gizmos.mesh();
+        let (gizmo_mesh, material) = match &mut self.gizmo_batch {
+            Some((gizmo_mesh, material)) => {
+                gizmo_mesh.update(mesh);
+                (gizmo_mesh, material)
+            }
+            None => {
+                let mut material = DefaultMaterial::default();
+                material.set_shader(Shader::from_source(material.vertex_shader(), material.fragment_shader()));
+                let (gizmo_mesh, material) = self.gizmo_batch.insert((InternalMesh::build(mesh), material));
+                (gizmo_mesh, material)
+            }
+        };
+
+        material.set_uniform(PROJECTION_UNIFORM, ShaderUniform::Mat4(self.camera.projection_matrix()));
+        material.set_uniform(MODEL_UNIFORM, ShaderUniform::Mat4(Transform::IDENTITY.flatten()));
+        material.bind();
+        gizmo_mesh.draw();
+    }
+
     fn events(&mut self, app: &mut T) -> Vec<Event> {
         let mut events: Vec<Event> = self.triggered_events.drain(..).collect();
 
+        let mut window_events = Vec::new();
         for (_, event) in glfw::flush_messages(&self.window.events) {
-            events.push(event.into());
+            match Event::try_from(event) {
+                Ok(Event::MouseMove(x, y)) => {
+                    let (x, y) = self.window.to_logical(x, y);
+                    window_events.push(Event::MouseMove(x, y));
+                }
+                Ok(event) => window_events.push(event),
+                Err(_) => {}
+            }
+        }
+        // Replays replace the live events, but clicking the close button of the window is still a
+        // request the app can veto
+        let window_close_requested = self.window.p_window.should_close();
+        events.extend(self.session.frame_events(window_events, self.gamepad_source.as_mut(), &mut self.input));
+
+        // Closing is only a request until the app confirmed it
+        let close_requested = window_close_requested || events.contains(&Event::Close);
+        if close_requested {
+            self.window.p_window.set_should_close(false);
         }
 
         for event in &events {
             match *event {
                 Event::MouseEnter(true) => {
-                    let cursor_pos = self.window.p_window.get_cursor_pos();
-                    self.mouse_pos = Vec2::new(cursor_pos.0 as f32, cursor_pos.1 as f32);
-                }
-                Event::MouseMove(x, y) => {
-                    self.mouse_pos = Vec2::new(x as f32, y as f32);
+                    self.sync_mouse_position();
                 }
-                _ => {}
+                Event::Resize(..) | Event::ContentScale(..) => self.resize(),
+                _ => self.input.process(event),
             }
 
             self.handle_event(event, app);
         }
 
+        self.handle_actions(app);
+
         app.event_handler(Commands::new(self), &events);
 
+        if close_requested && !self.window.p_window.should_close() && app.on_close_requested(Commands::new(self)) {
+            debug!("Close request confirmed");
+            self.window.p_window.set_should_close(true);
+        }
+
         events
     }
 
+    fn sync_mouse_position(&mut self) {
+        let cursor_pos = self.session.cursor_position(self.window.cursor_position());
+        self.input.set_mouse_position(Vec2::new(cursor_pos.0 as f32, cursor_pos.1 as f32));
+    }
+
+    /// The GL viewport covers the framebuffer in physical pixels, the camera works in logical pixels.
+    fn resize(&mut self) {
+        let (width, height) = self.window.framebuffer_size();
+        // Minimizing reports a zero sized framebuffer
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.window.set_viewport(width, height);
+
+        let (width, height) = self.window.logical_size();
+        self.camera.viewport = Vec2::new(width, height);
+    }
+
     fn handle_event(&mut self, event: &Event, app: &mut T) {
         let event_callbacks = std::mem::take(&mut self.event_callbacks);
 
-        if let Some(callbacks) = event_callbacks.get(event) {
-            debug!("Handling event: {event:?}");
-            self.call_callbacks(callbacks, app);
+        if let Some(kind) = event.kind() {
+            for kind in std::iter::once(kind).chain(kind.without_modifiers()) {
+                if let Some(callbacks) = event_callbacks.get(&kind) {
+                    debug!("Handling event: {event:?}");
+                    self.call_callbacks(callbacks, app);
+                }
+            }
         }
 
         self.event_callbacks = event_callbacks;
     }
 
+    fn handle_actions(&mut self, app: &mut T) {
+        let action_callbacks = std::mem::take(&mut self.action_callbacks);
+
+        for (action, callbacks) in &action_callbacks {
+            if self.input_map.just_pressed(action, &self.input) {
+                debug!("Handling action: '{action}'");
+                self.call_callbacks(callbacks, app);
+            }
+        }
+
+        self.action_callbacks = action_callbacks;
+    }
+
     fn call_callbacks(&mut self, callbacks: &Vec<EventCallback<T>>, app: &mut T) {
         for callback in callbacks {
             debug!("Calling event callback");
@@ -323,7 +926,10 @@ impl<T: PaperApp> Debug for Paper<T> {
             .field("delta_time", &self.delta_time)
             .field("fixed_time_step", &self.fixed_time_step)
             .field("fixed_delta_time", &self.fixed_delta_time)
-            .field("mouse_pos", &self.mouse_pos)
+            .field("input", &self.input)
+            .field("plugins", &self.plugins)
+            .field("schedule", &self.schedule)
+            .field("resources", &self.resources)
             .field("triggered_events", &self.triggered_events)
             .finish()
     }
@@ -349,6 +955,80 @@ impl<T: PaperApp> Commandable for Paper<T> {
         self.delta_time as f32
     }
 
+    fn window(&self) -> &Window {
+        &self.window
+    }
+
+    fn window_mut(&mut self) -> &mut Window {
+        &mut self.window
+    }
+
+    fn is_replaying(&self) -> bool {
+        self.session.is_replaying()
+    }
+
+    fn send_event(&mut self, event: Event) {
+        self.triggered_events.push(event);
+    }
+
+    fn register_user_event(&mut self, type_id: TypeId, update: fn(&mut Resources)) {
+        self.user_events.insert(type_id, update);
+    }
+
+    fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    fn resources_mut(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+
+    fn set_system_enabled(&mut self, label: &str, enabled: bool) {
+        self.schedule.set_enabled(label, enabled);
+    }
+
+    fn is_system_enabled(&self, label: &str) -> bool {
+        self.schedule.is_enabled(label)
+    }
+
+    fn clipboard(&self) -> Option<String> {
+        self.window.p_window.get_clipboard_string()
+    }
+
+    fn set_clipboard(&mut self, text: &str) {
+        self.window.p_window.set_clipboard_string(text);
+    }
+
+    fn input(&self) -> &InputState {
+        &self.input
+    }
+
+    fn cursor_mode(&self) -> CursorMode {
+        self.window.cursor_mode()
+    }
+
+    fn set_cursor_mode(&mut self, mode: CursorMode) {
+        self.window.set_cursor_mode(mode);
+        // Switching modes can move the cursor, which must not show up as mouse movement
+        self.sync_mouse_position();
+    }
+
+    fn set_raw_mouse_motion(&mut self, enabled: bool) -> bool {
+        self.window.set_raw_mouse_motion(enabled)
+    }
+
+    fn set_cursor(&mut self, cursor: &Cursor) {
+        self.window.set_cursor(cursor);
+    }
+
+    fn input_map(&self) -> &InputMap {
+        &self.input_map
+    }
+
+    fn input_map_mut(&mut self) -> &mut InputMap {
+        &mut self.input_map
+    }
+
     fn fixed_delta_time(&self) -> f32 {
         self.fixed_delta_time as f32
     }
@@ -357,6 +1037,22 @@ impl<T: PaperApp> Commandable for Paper<T> {
         self.add_mesh(mesh)
     }
 
+    fn update_mesh(&mut self, mesh_id: MeshId, mesh: Mesh) -> bool {
+        self.update_mesh(mesh_id, mesh)
+    }
+
+    fn add_mesh_deduplicated(&mut self, mesh: Mesh) -> MeshId {
+        self.add_mesh_deduplicated(mesh)
+    }
+
+    fn remove_mesh(&mut self, mesh_id: MeshId) -> bool {
+        self.remove_mesh(mesh_id)
+    }
+
+    fn remove_material(&mut self, material_id: MaterialId) -> bool {
+        self.remove_material(material_id)
+    }
+
     fn add_material(&mut self, material: Box<dyn Material>) -> MaterialId {
         self.add_material(material)
     }
@@ -365,6 +1061,14 @@ impl<T: PaperApp> Commandable for Paper<T> {
         self.add_entity(entity)
     }
 
+    fn add_svg(&mut self, svg: &Svg, transform: Transform) -> EntityId {
+        self.add_svg(svg, transform)
+    }
+
+    fn gizmos(&mut self) -> &mut Gizmos {
+        self.gizmos()
+    }
+
     fn remove_entity(&mut self, entity_id: EntityId) -> Option<Entity> {
         self.remove_entity(entity_id)
     }
@@ -388,3 +1092,42 @@ impl<T: PaperApp> Commandable for Paper<T> {
         }
     }
 }
+
+impl<T: PaperApp> StateContext for Paper<T> {
+    type App = T;
+    type Hook = SystemFn<T>;
+
+    fn state_stack<S: State>(&mut self) -> Option<&mut StateStack<S>> {
+        self.resource_mut::<StateStack<S>>()
+    }
+
+    fn spawned_entities(&self) -> &[SpawnedEntity] {
+        self.spawned_entities.as_deref().unwrap_or_default()
+    }
+
+    fn set_spawn_owner(&mut self, owner: Option<TypeId>) {
+        self.spawn_owner = owner;
+    }
+
+    fn run_hook(&mut self, hook: &mut SystemFn<T>, app: &mut T) {
+        hook(Commands::new(self), app);
+    }
+
+    fn despawn(&mut self, id: EntityId) {
+        if self.get_entity(&id).is_some() {
+            self.remove_entity(id);
+        }
+    }
+}
+
+// ---------------< PRIVATE >---------------
+
+/// Who is responsible for freeing a deduplicated mesh. Meshes are only shared with meshes of the same
+/// owner, so the removal of a mesh never depends on both the user and the entities freeing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum MeshOwner {
+    /// Freed through [`Paper::remove_mesh`].
+    User,
+    /// Freed with the last primitive entity using it.
+    Primitive,
+}
@@ -0,0 +1,33 @@
+use crate::{Paper, PaperApp};
+
+/// Reusable functionality that is added to a [`Paper`] before it runs.
+///
+/// Plugins get full access to the [`Paper`] they are added to and can register callbacks, systems,
+/// resources, materials and everything else an application could set up by hand. Closures taking a
+/// `&mut Paper<T>` are plugins as well.
+///
+/// Input state and frame timing are not plugins yet. They are updated by the run loop before the
+/// first [`Stage`](crate::Stage) and before event callbacks, where plugins have no way to hook in.
+pub trait Plugin<T: PaperApp> {
+    fn build(&self, paper: &mut Paper<T>);
+
+    /// Used to detect a plugin that is added twice, defaults to the type name.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Whether the plugin may be added more than once.
+    fn is_unique(&self) -> bool {
+        true
+    }
+}
+
+impl<T: PaperApp, F: Fn(&mut Paper<T>)> Plugin<T> for F {
+    fn build(&self, paper: &mut Paper<T>) {
+        self(paper);
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
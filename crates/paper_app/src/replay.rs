@@ -0,0 +1,543 @@
+use std::{
+    collections::VecDeque,
+    fmt, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use log::{error, info};
+use paper_input::{
+    Event, GAMEPAD_AXIS_COUNT, Gamepad, GamepadButton, GamepadSnapshot, GamepadSource, InputState, VirtualGamepadSource,
+};
+use paper_math::Vec2;
+use paper_utils::default;
+
+/// Everything a frame received from outside the application.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordedFrame {
+    pub delta_time:       f64,
+    pub events:           Vec<Event>,
+    /// The connected gamepads, `None` if they did not change since the previous frame.
+    pub gamepads:         Option<Vec<(Gamepad, GamepadSnapshot)>>,
+    /// Cursor positions read from the window, e.g. after the cursor entered it.
+    pub cursor_positions: Vec<(f64, f64)>,
+}
+
+/// The input of a session, frame by frame, used to replay it with [`Paper::with_replay`](crate::Paper::with_replay).
+///
+/// The first frame holds the input read during [`PaperApp::setup`](crate::PaperApp::setup).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    frames: Vec<RecordedFrame>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    pub fn push_frame(&mut self, frame: RecordedFrame) {
+        self.frames.push(frame);
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        std::fs::read_to_string(path)?.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# paper recording")?;
+
+        for frame in &self.frames {
+            writeln!(f, "frame {}", frame.delta_time)?;
+            for event in &frame.events {
+                writeln!(f, "event {event}")?;
+            }
+            if let Some(gamepads) = &frame.gamepads {
+                writeln!(f, "gamepads")?;
+                for (gamepad, snapshot) in gamepads {
+                    let buttons = GamepadButton::ALL
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, button)| snapshot.button(**button))
+                        .fold(0u32, |bits, (i, _)| bits | 1 << i);
+                    write!(f, "gamepad {} {buttons}", gamepad.id())?;
+                    for value in snapshot.axes {
+                        write!(f, " {value}")?;
+                    }
+                    writeln!(f, " {}", snapshot.name)?;
+                }
+            }
+            for (x, y) in &frame.cursor_positions {
+                writeln!(f, "cursor {x} {y}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Recording {
+    type Err = ParseRecordingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut recording = Self::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let err = || ParseRecordingError { line: i + 1, content: line.to_string() };
+
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+            if keyword == "frame" {
+                recording.frames.push(RecordedFrame { delta_time: rest.parse().map_err(|_| err())?, ..default() });
+                continue;
+            }
+
+            let frame = recording.frames.last_mut().ok_or_else(err)?;
+            match keyword {
+                "event" => frame.events.push(rest.parse().map_err(|_| err())?),
+                "gamepads" => frame.gamepads = Some(Vec::new()),
+                "gamepad" => {
+                    let gamepad = parse_gamepad(rest).ok_or_else(err)?;
+                    frame.gamepads.as_mut().ok_or_else(err)?.push(gamepad);
+                }
+                "cursor" => {
+                    let (x, y) = rest.split_once(' ').ok_or_else(err)?;
+                    frame.cursor_positions.push((x.parse().map_err(|_| err())?, y.parse().map_err(|_| err())?));
+                }
+                _ => return Err(err()),
+            }
+        }
+
+        Ok(recording)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRecordingError {
+    line:    usize,
+    content: String,
+}
+
+impl fmt::Display for ParseRecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid recording line {}: '{}'", self.line, self.content)
+    }
+}
+
+impl std::error::Error for ParseRecordingError {}
+
+/// Plays a [`Recording`] into an [`InputState`] frame by frame, without a window or GL context.
+///
+/// [`Paper::replay`](crate::Paper::replay) needs a window since it also renders. This feeds the same
+/// events and gamepads into the input state as [`Paper::run`](crate::Paper::run) does, so input
+/// handling can be tested on machines without a display.
+#[derive(Debug)]
+pub struct InputReplay {
+    session: Session,
+    input:   InputState,
+    events:  Vec<Event>,
+    started: bool,
+}
+
+impl InputReplay {
+    pub fn new(recording: Recording) -> Self {
+        Self { session: Session::replay(recording), input: InputState::new(), events: Vec::new(), started: false }
+    }
+
+    /// Plays the next frame and returns its delta time, `None` once all frames have been played.
+    /// Like the frame loop, this skips the input read during setup.
+    pub fn next_frame(&mut self) -> Option<f64> {
+        if self.started {
+            self.input.end_frame();
+        }
+        self.started = true;
+        self.events.clear();
+
+        let delta_time = self.session.begin_frame(0.0)?;
+        self.events = self.session.frame_events(Vec::new(), &mut VirtualGamepadSource::new(), &mut self.input);
+        for event in &self.events {
+            if *event == Event::MouseEnter(true) {
+                let position = self.input.mouse_position();
+                let (x, y) = self.session.cursor_position((position.x as f64, position.y as f64));
+                self.input.set_mouse_position(Vec2::new(x as f32, y as f32));
+            } else {
+                self.input.process(event);
+            }
+        }
+
+        Some(delta_time)
+    }
+
+    pub fn input(&self) -> &InputState {
+        &self.input
+    }
+
+    pub fn input_mut(&mut self) -> &mut InputState {
+        &mut self.input
+    }
+
+    /// The events of the current frame, including gamepad events.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+}
+
+/// Whether the input of the running session comes from the platform, possibly being recorded, or from
+/// a [`Recording`].
+#[derive(Debug, Default)]
+pub(crate) enum Session {
+    #[default]
+    Live,
+    Recording {
+        recording: Recording,
+        path:      PathBuf,
+        gamepads:  Vec<(Gamepad, GamepadSnapshot)>,
+    },
+    Replaying {
+        frames:   VecDeque<RecordedFrame>,
+        frame:    RecordedFrame,
+        gamepads: Vec<(Gamepad, GamepadSnapshot)>,
+    },
+}
+
+impl Session {
+    pub(crate) fn record(path: impl AsRef<Path>) -> Self {
+        Self::Recording {
+            recording: Recording { frames: vec![RecordedFrame::default()] },
+            path:      path.as_ref().to_path_buf(),
+            gamepads:  Vec::new(),
+        }
+    }
+
+    pub(crate) fn replay(recording: Recording) -> Self {
+        let mut frames = VecDeque::from(recording.frames);
+        let frame = frames.pop_front().unwrap_or_default();
+        Self::Replaying { frames, frame, gamepads: Vec::new() }
+    }
+
+    pub(crate) fn is_replaying(&self) -> bool {
+        matches!(self, Self::Replaying { .. })
+    }
+
+    /// Starts a new frame and returns its delta time, `None` once a replay is finished.
+    pub(crate) fn begin_frame(&mut self, elapsed: f64) -> Option<f64> {
+        match self {
+            Self::Live => Some(elapsed),
+            Self::Recording { recording, .. } => {
+                recording.frames.push(RecordedFrame { delta_time: elapsed, ..default() });
+                Some(elapsed)
+            }
+            Self::Replaying { frames, frame, .. } => {
+                *frame = frames.pop_front()?;
+                Some(frame.delta_time)
+            }
+        }
+    }
+
+    /// Records the events of the window, while replaying they are replaced with the recorded ones.
+    pub(crate) fn window_events(&mut self, events: Vec<Event>) -> Vec<Event> {
+        match self {
+            Self::Live => events,
+            Self::Recording { recording, .. } => {
+                if let Some(frame) = recording.frames.last_mut() {
+                    frame.events.extend(events.iter().cloned());
+                }
+                events
+            }
+            Self::Replaying { frame, .. } => std::mem::take(&mut frame.events),
+        }
+    }
+
+    /// The events of the frame, followed by the gamepad events resulting from applying the gamepads to
+    /// `input`. Both come from the recording while replaying.
+    pub(crate) fn frame_events(
+        &mut self,
+        window_events: Vec<Event>,
+        gamepad_source: &mut dyn GamepadSource,
+        input: &mut InputState,
+    ) -> Vec<Event> {
+        let mut events = self.window_events(window_events);
+        let gamepads = self.gamepads(gamepad_source);
+        events.extend(input.update_gamepads(gamepads));
+        events
+    }
+
+    pub(crate) fn gamepads(&mut self, source: &mut dyn GamepadSource) -> Vec<(Gamepad, GamepadSnapshot)> {
+        match self {
+            Self::Live => source.poll(),
+            Self::Recording { recording, gamepads, .. } => {
+                let polled = source.poll();
+                if polled != *gamepads
+                    && let Some(frame) = recording.frames.last_mut()
+                {
+                    frame.gamepads = Some(polled.clone());
+                    *gamepads = polled.clone();
+                }
+                polled
+            }
+            Self::Replaying { frame, gamepads, .. } => {
+                if let Some(recorded) = frame.gamepads.take() {
+                    *gamepads = recorded;
+                }
+                gamepads.clone()
+            }
+        }
+    }
+
+    pub(crate) fn cursor_position(&mut self, position: (f64, f64)) -> (f64, f64) {
+        match self {
+            Self::Live => position,
+            Self::Recording { recording, .. } => {
+                if let Some(frame) = recording.frames.last_mut() {
+                    frame.cursor_positions.push(position);
+                }
+                position
+            }
+            Self::Replaying { frame, .. } => {
+                if frame.cursor_positions.is_empty() {
+                    return position;
+                }
+                frame.cursor_positions.remove(0)
+            }
+        }
+    }
+
+    /// Saves a recording, called when the application exits.
+    pub(crate) fn finish(&mut self) {
+        match std::mem::take(self) {
+            Self::Recording { recording, path, .. } => match recording.save(&path) {
+                Ok(()) => info!("Saved recording of {} frames to {}", recording.frames.len(), path.display()),
+                Err(e) => error!("Failed to save recording to {}: {e}", path.display()),
+            },
+            Self::Replaying { frames, .. } => info!("Replay stopped with {} frames left", frames.len()),
+            Self::Live => {}
+        }
+    }
+}
+
+// ---------------< PRIVATE >---------------
+
+fn parse_gamepad(s: &str) -> Option<(Gamepad, GamepadSnapshot)> {
+    let mut parts = s.splitn(3 + GAMEPAD_AXIS_COUNT, ' ');
+    let gamepad = Gamepad(parts.next()?.parse().ok()?);
+    let buttons: u32 = parts.next()?.parse().ok()?;
+
+    let mut snapshot = GamepadSnapshot::default();
+    for (i, button) in GamepadButton::ALL.into_iter().enumerate() {
+        snapshot.set_button(button, buttons & 1 << i != 0);
+    }
+    for axis in &mut snapshot.axes {
+        *axis = parts.next()?.parse().ok()?;
+    }
+    snapshot.name = parts.next().unwrap_or_default().to_string();
+
+    Some((gamepad, snapshot))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use paper_input::{Action, Binding, GamepadAxis, InputMap, Key, Modifiers, MouseButton, VirtualGamepadSource};
+
+    use super::*;
+
+    fn pad() -> GamepadSnapshot {
+        GamepadSnapshot::new("Xbox Controller (wired)")
+            .with_button(GamepadButton::A, true)
+            .with_button(GamepadButton::DpadLeft, true)
+            .with_axis(GamepadAxis::LeftX, -0.25)
+            .with_axis(GamepadAxis::RightTrigger, 0.1)
+    }
+
+    fn recording() -> Recording {
+        let mut recording = Recording::new();
+        recording.push_frame(RecordedFrame {
+            delta_time:       0.0,
+            events:           vec![Event::Resize(800, 600)],
+            gamepads:         Some(vec![(Gamepad(0), pad()), (Gamepad(2), GamepadSnapshot::default())]),
+            cursor_positions: vec![(10.5, 20.25)],
+        });
+        recording.push_frame(RecordedFrame {
+            delta_time:       1.0 / 60.0,
+            events:           vec![
+                Event::Key {
+                    key:       Key::S,
+                    action:    Action::Press,
+                    modifiers: Modifiers::CONTROL,
+                    scancode:  39,
+                },
+                Event::Text('é'),
+                Event::Text(' '),
+                Event::MouseMove(1.125, -3.0),
+                Event::MouseButton(MouseButton::Left, Action::Release),
+                Event::FileDrop(vec![PathBuf::from("/tmp/my file.svg"), PathBuf::from("relative/b.txt")]),
+                Event::GamepadButton(Gamepad(0), GamepadButton::A, Action::Press),
+            ],
+            gamepads:         None,
+            cursor_positions: vec![],
+        });
+        recording.push_frame(RecordedFrame {
+            delta_time:       0.016,
+            events:           vec![Event::Close],
+            gamepads:         Some(vec![]),
+            cursor_positions: vec![(0.0, 0.0), (-1.0, 1e-3)],
+        });
+        recording
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let recording = recording();
+        let text = recording.to_string();
+
+        assert_eq!(text.parse::<Recording>(), Ok(recording));
+        assert!(text.starts_with("# paper recording\nframe 0\n"), "{text}");
+
+        for invalid in ["event Close", "frame x", "frame 0\nevent Nope", "frame 0\ngamepad 0 0", "frame 0\ncursor 1"] {
+            assert!(invalid.parse::<Recording>().is_err(), "'{invalid}' should be rejected");
+        }
+        let error = "frame 0\n\nbogus".parse::<Recording>().unwrap_err();
+        assert_eq!(error.to_string(), "invalid recording line 3: 'bogus'");
+    }
+
+    #[test]
+    fn replay_replaces_live_input() {
+        let mut session = Session::replay(recording());
+        let mut live = VirtualGamepadSource::new();
+        live.connect(Gamepad(5), GamepadSnapshot::new("Live"));
+        let live_events = || {
+            vec![Event::Key { key: Key::Q, action: Action::Press, modifiers: Modifiers::NONE, scancode: 0 }]
+        };
+
+        // The first frame is the input read during setup
+        assert!(session.is_replaying());
+        assert_eq!(session.window_events(live_events()), [Event::Resize(800, 600)]);
+        assert_eq!(session.gamepads(&mut live), [(Gamepad(0), pad()), (Gamepad(2), GamepadSnapshot::default())]);
+        assert_eq!(session.cursor_position((99.0, 99.0)), (10.5, 20.25));
+        assert_eq!(session.cursor_position((99.0, 99.0)), (99.0, 99.0));
+
+        // Delta times come from the recording, gamepads stay connected while unchanged
+        assert_eq!(session.begin_frame(5.0), Some(1.0 / 60.0));
+        assert_eq!(session.window_events(live_events()).len(), 7);
+        assert_eq!(session.window_events(live_events()), []);
+        assert_eq!(session.gamepads(&mut live).len(), 2);
+
+        assert_eq!(session.begin_frame(5.0), Some(0.016));
+        assert_eq!(session.window_events(live_events()), [Event::Close]);
+        assert_eq!(session.gamepads(&mut live), []);
+        assert_eq!(session.cursor_position((99.0, 99.0)), (0.0, 0.0));
+        assert_eq!(session.cursor_position((99.0, 99.0)), (-1.0, 1e-3));
+
+        assert_eq!(session.begin_frame(5.0), None);
+        assert_eq!(session.begin_frame(5.0), None);
+    }
+
+    #[test]
+    fn recorded_session_replays_the_same_input() {
+        let mut source = VirtualGamepadSource::new();
+        let mut session = Session::record("unused.rec");
+
+        session.window_events(vec![Event::Focus(true)]);
+        source.connect(Gamepad(1), pad());
+        session.gamepads(&mut source);
+
+        session.begin_frame(0.5);
+        session.window_events(vec![Event::Text('a'), Event::MouseScroll(0.0, -1.5)]);
+        session.gamepads(&mut source);
+        session.cursor_position((4.0, 2.0));
+
+        session.begin_frame(0.25);
+        source.disconnect(Gamepad(1));
+        session.gamepads(&mut source);
+
+        let Session::Recording { recording, .. } = std::mem::take(&mut session) else {
+            panic!("expected a recording session");
+        };
+        let recording: Recording = recording.to_string().parse().unwrap();
+        assert_eq!(recording.frames().len(), 3);
+        assert_eq!(recording.frames()[1].gamepads, None);
+
+        let mut session = Session::replay(recording);
+        let mut live = VirtualGamepadSource::new();
+        assert_eq!(session.window_events(vec![]), [Event::Focus(true)]);
+        assert_eq!(session.gamepads(&mut live), [(Gamepad(1), pad())]);
+
+        assert_eq!(session.begin_frame(1.0), Some(0.5));
+        assert_eq!(session.window_events(vec![]), [Event::Text('a'), Event::MouseScroll(0.0, -1.5)]);
+        assert_eq!(session.gamepads(&mut live), [(Gamepad(1), pad())]);
+        assert_eq!(session.cursor_position((0.0, 0.0)), (4.0, 2.0));
+
+        assert_eq!(session.begin_frame(1.0), Some(0.25));
+        assert_eq!(session.gamepads(&mut live), []);
+        assert_eq!(session.begin_frame(1.0), None);
+    }
+
+    #[test]
+    fn recording_file_plays_without_a_window() {
+        let key = |key, action| Event::Key { key, action, modifiers: Modifiers::NONE, scancode: 0 };
+        let mut recording = Recording::new();
+        recording.push_frame(RecordedFrame { events: vec![key(Key::Q, Action::Press)], ..default() });
+        recording.push_frame(RecordedFrame {
+            delta_time:       0.5,
+            events:           vec![key(Key::W, Action::Press), Event::MouseEnter(true)],
+            gamepads:         Some(vec![(Gamepad(0), pad())]),
+            cursor_positions: vec![(3.0, 4.0)],
+        });
+        recording.push_frame(RecordedFrame {
+            delta_time: 0.25,
+            events: vec![Event::MouseMove(5.0, 4.0), Event::MouseScroll(0.0, 1.0)],
+            ..default()
+        });
+        recording.push_frame(RecordedFrame {
+            delta_time: 0.25,
+            events: vec![key(Key::W, Action::Release), Event::Close],
+            gamepads: Some(vec![]),
+            ..default()
+        });
+
+        let path = std::env::temp_dir().join(format!("paper_recording_{}.rec", std::process::id()));
+        recording.save(&path).unwrap();
+        let loaded = Recording::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let input_map = InputMap::new().with_binding("up", Binding::new(Key::W));
+        let mut replay = InputReplay::new(loaded.unwrap());
+
+        // The input read during setup is skipped, like in the frame loop
+        assert_eq!(replay.next_frame(), Some(0.5));
+        assert!(!replay.input().key_pressed(Key::Q));
+        assert!(input_map.just_pressed("up", replay.input()));
+        assert_eq!(replay.input().mouse_position(), Vec2::new(3.0, 4.0));
+        assert!(replay.input().gamepad_button_just_pressed(Gamepad(0), GamepadButton::A));
+        assert!(replay.events().contains(&Event::GamepadConnected(Gamepad(0))));
+
+        assert_eq!(replay.next_frame(), Some(0.25));
+        assert!(input_map.pressed("up", replay.input()) && !input_map.just_pressed("up", replay.input()));
+        assert_eq!(replay.input().mouse_delta(), Vec2::new(2.0, 0.0));
+        assert_eq!(replay.input().mouse_scroll(), Vec2::new(0.0, 1.0));
+        assert!(replay.input().gamepad_button_pressed(Gamepad(0), GamepadButton::A));
+
+        assert_eq!(replay.next_frame(), Some(0.25));
+        assert!(replay.input().key_just_released(Key::W));
+        assert_eq!(replay.input().mouse_delta(), Vec2::ZERO);
+        assert_eq!(replay.input().gamepads().count(), 0);
+        assert!(replay.events().contains(&Event::Close));
+
+        assert_eq!(replay.next_frame(), None);
+        assert!(replay.events().is_empty());
+    }
+}
@@ -0,0 +1,56 @@
+use std::any::{Any, TypeId};
+
+use hashbrown::HashMap;
+
+/// Typed singletons shared between the app, callbacks, systems and plugins.
+#[derive(Debug, Default)]
+pub struct Resources {
+    resources: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a resource, returning the previous one of the same type.
+    pub fn insert<R: 'static>(&mut self, resource: R) -> Option<R> {
+        self.resources.insert(TypeId::of::<R>(), Box::new(resource)).and_then(|old| old.downcast().ok()).map(|old| *old)
+    }
+
+    pub fn get<R: 'static>(&self) -> Option<&R> {
+        self.resources.get(&TypeId::of::<R>()).and_then(|resource| resource.downcast_ref())
+    }
+
+    pub fn get_mut<R: 'static>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut(&TypeId::of::<R>()).and_then(|resource| resource.downcast_mut())
+    }
+
+    /// Returns the resource, inserting the value returned by `init` if it does not exist yet.
+    pub fn get_or_insert_with<R: 'static>(&mut self, init: impl FnOnce() -> R) -> &mut R {
+        self.resources
+            .entry(TypeId::of::<R>())
+            .or_insert_with(|| Box::new(init()))
+            .downcast_mut()
+            .expect("resource stored under the type id of another type")
+    }
+
+    pub fn remove<R: 'static>(&mut self) -> Option<R> {
+        self.resources
+            .remove(&TypeId::of::<R>())
+            .and_then(|resource| resource.downcast().ok())
+            .map(|resource| *resource)
+    }
+
+    pub fn contains<R: 'static>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<R>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+}
@@ -0,0 +1,449 @@
+use std::{
+    any::{Any, TypeId},
+    collections::VecDeque,
+    fmt::Debug,
+    hash::Hash,
+};
+
+use hashbrown::{HashMap, HashSet};
+use log::{debug, warn};
+
+use crate::{Commands, EntityId, Paper, PaperApp};
+
+/// A state of the application, usually an enum like `MainMenu`, `Playing`, `Paused`.
+pub trait State: Debug + Clone + Eq + Hash + 'static {}
+
+impl<S: Debug + Clone + Eq + Hash + 'static> State for S {}
+
+/// The active states of type `S`, the last one is the current state.
+///
+/// Changes are queued and applied at the start of the next frame, running the exit and enter hooks
+/// registered with [`Paper::on_exit`] and [`Paper::on_enter`].
+#[derive(Debug, Clone)]
+pub struct StateStack<S: State> {
+    stack: Vec<S>,
+    queue: VecDeque<Transition<S>>,
+}
+
+impl<S: State> StateStack<S> {
+    pub(crate) fn new(initial: S) -> Self {
+        Self { stack: Vec::new(), queue: VecDeque::from([Transition::Push(initial)]) }
+    }
+
+    pub fn current(&self) -> Option<&S> {
+        self.stack.last()
+    }
+
+    /// All active states, the bottom of the stack first.
+    pub fn stack(&self) -> &[S] {
+        &self.stack
+    }
+
+    pub fn contains(&self, state: &S) -> bool {
+        self.stack.contains(state)
+    }
+
+    /// Exits all active states and enters `state`.
+    pub fn set(&mut self, state: S) {
+        self.queue.push_back(Transition::Set(state));
+    }
+
+    /// Enters `state` on top of the current one, which stays active but is no longer current.
+    pub fn push(&mut self, state: S) {
+        self.queue.push_back(Transition::Push(state));
+    }
+
+    /// Exits the current state. The last state is never popped.
+    pub fn pop(&mut self) {
+        self.queue.push_back(Transition::Pop);
+    }
+}
+
+/// Run condition for systems that should only run while `state` is the current state.
+pub fn in_state<S: State>(state: S) -> impl Fn(&Commands) -> bool {
+    move |cmd| cmd.state::<S>() == Some(&state)
+}
+
+/// An entity spawned this frame and the state type whose hooks spawned it, `None` outside of hooks.
+pub(crate) type SpawnedEntity = (EntityId, Option<TypeId>);
+
+/// What state transitions need from [`Paper`], so they can run without a window in tests.
+pub(crate) trait StateContext {
+    type App;
+    type Hook;
+
+    fn state_stack<S: State>(&mut self) -> Option<&mut StateStack<S>>;
+
+    /// The entities spawned since the last call to [`StateDriver::finish_tracking`].
+    fn spawned_entities(&self) -> &[SpawnedEntity];
+
+    /// Marks the entities spawned from now on as spawned by the hooks of `owner`.
+    fn set_spawn_owner(&mut self, owner: Option<TypeId>);
+
+    fn run_hook(&mut self, hook: &mut Self::Hook, app: &mut Self::App);
+
+    fn despawn(&mut self, id: EntityId);
+}
+
+/// Type erased [`StateHooks`] so `Paper` can drive states of any type.
+pub(crate) trait StateDriver<T: PaperApp> {
+    fn apply_transitions(&mut self, paper: &mut Paper<T>, app: &mut T);
+
+    /// Attributes the entities spawned since the last call to the current state and resets the log cursor.
+    fn finish_tracking(&mut self, spawned: &[SpawnedEntity]);
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+pub(crate) struct StateHooks<S: State, C: StateContext> {
+    on_enter: HashMap<S, Vec<C::Hook>>,
+    on_exit:  HashMap<S, Vec<C::Hook>>,
+    scoped:   HashSet<S>,
+    /// Entities spawned per stack depth.
+    entities: Vec<Vec<EntityId>>,
+    depth:    usize,
+    tracked:  usize,
+}
+
+impl<S: State, C: StateContext> StateHooks<S, C> {
+    pub(crate) fn new() -> Self {
+        Self {
+            on_enter: HashMap::new(),
+            on_exit:  HashMap::new(),
+            scoped:   HashSet::new(),
+            entities: Vec::new(),
+            depth:    0,
+            tracked:  0,
+        }
+    }
+
+    pub(crate) fn add_enter_hook(&mut self, state: S, hook: C::Hook) {
+        self.on_enter.entry(state).or_default().push(hook);
+    }
+
+    pub(crate) fn add_exit_hook(&mut self, state: S, hook: C::Hook) {
+        self.on_exit.entry(state).or_default().push(hook);
+    }
+
+    pub(crate) fn set_scoped(&mut self, state: S, scoped: bool) {
+        if scoped {
+            self.scoped.insert(state);
+        } else {
+            self.scoped.remove(&state);
+        }
+    }
+
+    pub(crate) fn apply(&mut self, cx: &mut C, app: &mut C::App) {
+        cx.set_spawn_owner(Some(TypeId::of::<S>()));
+
+        while let Some(transition) = cx.state_stack::<S>().and_then(|stack| stack.queue.pop_front()) {
+            let Some(stack) = cx.state_stack::<S>() else {
+                break;
+            };
+
+            match transition {
+                Transition::Set(state) => {
+                    if stack.stack.len() == 1 && stack.current() == Some(&state) {
+                        continue;
+                    }
+                    while cx.state_stack::<S>().is_some_and(|stack| !stack.stack.is_empty()) {
+                        self.exit(cx, app);
+                    }
+                    self.enter(cx, app, state);
+                }
+                Transition::Push(state) => {
+                    if stack.contains(&state) {
+                        warn!("State {state:?} is already active");
+                        continue;
+                    }
+                    self.enter(cx, app, state);
+                }
+                Transition::Pop => {
+                    if stack.stack.len() <= 1 {
+                        warn!("Cannot pop the last state");
+                        continue;
+                    }
+                    self.exit(cx, app);
+                }
+            }
+        }
+
+        cx.set_spawn_owner(None);
+    }
+
+    pub(crate) fn finish(&mut self, spawned: &[SpawnedEntity]) {
+        self.track(spawned);
+        self.tracked = 0;
+    }
+
+    // ---------------< PRIVATE >---------------
+
+    /// Attributes new entities to the current state, unless the hooks of another state type spawned them.
+    fn track(&mut self, spawned: &[SpawnedEntity]) {
+        let new: Vec<EntityId> = spawned
+            .get(self.tracked..)
+            .unwrap_or_default()
+            .iter()
+            .filter(|(_, owner)| owner.is_none_or(|owner| owner == TypeId::of::<S>()))
+            .map(|(id, _)| *id)
+            .collect();
+        if self.depth > 0 && !new.is_empty() {
+            self.entities.resize_with(self.depth, Vec::new);
+            self.entities[self.depth - 1].extend(new);
+        }
+        self.tracked = spawned.len();
+    }
+
+    fn enter(&mut self, cx: &mut C, app: &mut C::App, state: S) {
+        debug!("Entering state {state:?}");
+        self.track(cx.spawned_entities());
+
+        if let Some(stack) = cx.state_stack::<S>() {
+            stack.stack.push(state.clone());
+            self.depth = stack.stack.len();
+        }
+
+        if let Some(hooks) = self.on_enter.get_mut(&state) {
+            for hook in hooks {
+                cx.run_hook(hook, app);
+            }
+        }
+
+        self.track(cx.spawned_entities());
+    }
+
+    fn exit(&mut self, cx: &mut C, app: &mut C::App) {
+        self.track(cx.spawned_entities());
+
+        let Some(state) = cx.state_stack::<S>().and_then(|stack| stack.current().cloned()) else {
+            return;
+        };
+        debug!("Exiting state {state:?}");
+
+        if let Some(hooks) = self.on_exit.get_mut(&state) {
+            for hook in hooks {
+                cx.run_hook(hook, app);
+            }
+        }
+
+        self.track(cx.spawned_entities());
+
+        if let Some(stack) = cx.state_stack::<S>() {
+            stack.stack.pop();
+            self.depth = stack.stack.len();
+        }
+
+        let entities = self.entities.get_mut(self.depth).map(std::mem::take).unwrap_or_default();
+        if self.scoped.contains(&state) {
+            for id in entities {
+                cx.despawn(id);
+            }
+        }
+    }
+}
+
+impl<S: State, T: PaperApp + 'static> StateDriver<T> for StateHooks<S, Paper<T>> {
+    fn apply_transitions(&mut self, paper: &mut Paper<T>, app: &mut T) {
+        self.apply(paper, app);
+    }
+
+    fn finish_tracking(&mut self, spawned: &[SpawnedEntity]) {
+        self.finish(spawned);
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// ---------------< PRIVATE >---------------
+
+#[derive(Debug, Clone)]
+enum Transition<S> {
+    Set(S),
+    Push(S),
+    Pop,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Resources;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Screen {
+        Menu,
+        Game,
+        Pause,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Weather {
+        Sun,
+        Rain,
+    }
+
+    type Log = Vec<String>;
+    type Hook = Box<dyn FnMut(&mut TestContext, &mut Log)>;
+
+    /// Stands in for `Paper`, entities are only ids and hooks write to a log.
+    #[derive(Default)]
+    struct TestContext {
+        resources: Resources,
+        spawned:   Vec<SpawnedEntity>,
+        owner:     Option<TypeId>,
+        entities:  HashMap<String, EntityId>,
+        alive:     HashSet<EntityId>,
+    }
+
+    impl TestContext {
+        fn spawn(&mut self, name: &str) {
+            let id = EntityId::new();
+            self.entities.insert(name.to_string(), id);
+            self.alive.insert(id);
+            self.spawned.push((id, self.owner));
+        }
+
+        fn is_alive(&self, name: &str) -> bool {
+            self.alive.contains(&self.entities[name])
+        }
+
+        fn stack<S: State>(&mut self) -> &mut StateStack<S> {
+            self.state_stack::<S>().unwrap()
+        }
+    }
+
+    impl StateContext for TestContext {
+        type App = Log;
+        type Hook = Hook;
+
+        fn state_stack<S: State>(&mut self) -> Option<&mut StateStack<S>> {
+            self.resources.get_mut::<StateStack<S>>()
+        }
+
+        fn spawned_entities(&self) -> &[SpawnedEntity] {
+            &self.spawned
+        }
+
+        fn set_spawn_owner(&mut self, owner: Option<TypeId>) {
+            self.owner = owner;
+        }
+
+        fn run_hook(&mut self, hook: &mut Hook, app: &mut Log) {
+            hook(self, app);
+        }
+
+        fn despawn(&mut self, id: EntityId) {
+            self.alive.remove(&id);
+        }
+    }
+
+    /// Hooks that log entering and exiting every state in `states`, spawning an entity named after
+    /// the state when entering it.
+    fn hooks<S: State>(cx: &mut TestContext, initial: S, states: &[S]) -> StateHooks<S, TestContext> {
+        cx.resources.insert(StateStack::new(initial));
+
+        let mut hooks = StateHooks::new();
+        for state in states {
+            let name = format!("{state:?}");
+            let enter: Hook = Box::new(move |cx, log| {
+                cx.spawn(&name);
+                log.push(format!("enter {name}"));
+            });
+            hooks.add_enter_hook(state.clone(), enter);
+
+            let name = format!("{state:?}");
+            let exit: Hook = Box::new(move |_, log| log.push(format!("exit {name}")));
+            hooks.add_exit_hook(state.clone(), exit);
+        }
+        hooks
+    }
+
+    /// Applies the transitions of a frame like `Paper` does.
+    fn frame<S: State>(hooks: &mut StateHooks<S, TestContext>, cx: &mut TestContext, log: &mut Log) {
+        hooks.apply(cx, log);
+        hooks.finish(&std::mem::take(&mut cx.spawned));
+    }
+
+    #[test]
+    fn set_push_and_pop_run_hooks_in_order() {
+        let (mut cx, mut log) = (TestContext::default(), Log::new());
+        let mut hooks = hooks(&mut cx, Screen::Menu, &[Screen::Menu, Screen::Game, Screen::Pause]);
+
+        frame(&mut hooks, &mut cx, &mut log);
+        assert_eq!(log, ["enter Menu"]);
+
+        cx.stack().set(Screen::Game);
+        cx.stack().push(Screen::Pause);
+        cx.stack().push(Screen::Game);
+        frame(&mut hooks, &mut cx, &mut log);
+        assert_eq!(log[1..], ["exit Menu", "enter Game", "enter Pause"]);
+        assert_eq!(cx.stack::<Screen>().stack(), [Screen::Game, Screen::Pause]);
+
+        // Setting exits the whole stack from the top
+        cx.stack().set(Screen::Menu);
+        frame(&mut hooks, &mut cx, &mut log);
+        assert_eq!(log[4..], ["exit Pause", "exit Game", "enter Menu"]);
+
+        cx.stack().set(Screen::Menu);
+        cx.stack::<Screen>().pop();
+        cx.stack().push(Screen::Pause);
+        cx.stack::<Screen>().pop();
+        frame(&mut hooks, &mut cx, &mut log);
+        assert_eq!(log[7..], ["enter Pause", "exit Pause"]);
+        assert_eq!(cx.stack::<Screen>().stack(), [Screen::Menu]);
+    }
+
+    #[test]
+    fn scoped_pop_despawns_only_the_overlay() {
+        let (mut cx, mut log) = (TestContext::default(), Log::new());
+        let mut hooks = hooks(&mut cx, Screen::Game, &[Screen::Game, Screen::Pause]);
+        hooks.set_scoped(Screen::Game, true);
+        hooks.set_scoped(Screen::Pause, true);
+
+        frame(&mut hooks, &mut cx, &mut log);
+        cx.spawn("player");
+
+        cx.stack().push(Screen::Pause);
+        frame(&mut hooks, &mut cx, &mut log);
+        cx.spawn("pause_menu");
+
+        cx.stack::<Screen>().pop();
+        frame(&mut hooks, &mut cx, &mut log);
+        assert!(!cx.is_alive("Pause") && !cx.is_alive("pause_menu"));
+        assert!(cx.is_alive("Game") && cx.is_alive("player"));
+
+        cx.stack().set(Screen::Menu);
+        frame(&mut hooks, &mut cx, &mut log);
+        assert!(!cx.is_alive("Game") && !cx.is_alive("player"));
+    }
+
+    #[test]
+    fn hooks_of_other_state_types_are_not_scoped() {
+        let (mut cx, mut log) = (TestContext::default(), Log::new());
+        let mut screens = hooks(&mut cx, Screen::Game, &[Screen::Pause]);
+        let mut weather = hooks(&mut cx, Weather::Sun, &[Weather::Rain]);
+        screens.set_scoped(Screen::Pause, true);
+
+        let mut frame =
+            |screens: &mut StateHooks<Screen, _>, weather: &mut StateHooks<Weather, _>, cx: &mut TestContext| {
+                screens.apply(cx, &mut log);
+                weather.apply(cx, &mut log);
+                let spawned = std::mem::take(&mut cx.spawned);
+                screens.finish(&spawned);
+                weather.finish(&spawned);
+            };
+
+        frame(&mut screens, &mut weather, &mut cx);
+        cx.stack().push(Screen::Pause);
+        cx.stack().set(Weather::Rain);
+        frame(&mut screens, &mut weather, &mut cx);
+        cx.spawn("paused_rain");
+
+        cx.stack::<Screen>().pop();
+        frame(&mut screens, &mut weather, &mut cx);
+        assert!(!cx.is_alive("Pause"));
+        assert!(!cx.is_alive("paused_rain"));
+        assert!(cx.is_alive("Rain"));
+    }
+}
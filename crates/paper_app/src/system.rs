@@ -0,0 +1,268 @@
+use std::collections::BTreeSet;
+
+use hashbrown::{HashMap, HashSet};
+use log::{error, warn};
+
+use crate::Commands;
+
+/// The point in a frame at which a [`System`] runs.
+///
+/// Per frame the stages run in the order `PreUpdate`, `Update`, `PostUpdate`, `PreRender`.
+/// `Fixed` systems run right after [`PaperApp::fixed_update`](crate::PaperApp::fixed_update).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    PreUpdate,
+    Update,
+    PostUpdate,
+    PreRender,
+    Fixed,
+}
+
+pub type SystemFn<T> = Box<dyn FnMut(Commands, &mut T)>;
+
+pub type RunCondition = Box<dyn Fn(&Commands) -> bool>;
+
+/// A function that runs once per [`Stage`].
+///
+/// Labels are used to order systems within a stage and to enable or disable them at runtime.
+/// Several systems can share a label.
+pub struct System<T> {
+    label:  Option<String>,
+    before: Vec<String>,
+    after:  Vec<String>,
+    run_if: Vec<RunCondition>,
+    func:   SystemFn<T>,
+}
+
+impl<T> System<T> {
+    pub fn new<F: FnMut(Commands, &mut T) + 'static>(run: F) -> Self {
+        Self { label: None, before: Vec::new(), after: Vec::new(), run_if: Vec::new(), func: Box::new(run) }
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Runs this system before all systems with the given label.
+    pub fn before(mut self, label: &str) -> Self {
+        self.before.push(label.to_string());
+        self
+    }
+
+    /// Runs this system after all systems with the given label.
+    pub fn after(mut self, label: &str) -> Self {
+        self.after.push(label.to_string());
+        self
+    }
+
+    /// Only runs the system while `condition` returns `true`, see [`in_state`](crate::in_state).
+    pub fn run_if<C: Fn(&Commands) -> bool + 'static>(mut self, condition: C) -> Self {
+        self.run_if.push(Box::new(condition));
+        self
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub(crate) fn conditions_met(&self, cmd: &Commands) -> bool {
+        self.run_if.iter().all(|condition| condition(cmd))
+    }
+
+    pub(crate) fn run(&mut self, cmd: Commands, app: &mut T) {
+        (self.func)(cmd, app);
+    }
+}
+
+impl<T, F: FnMut(Commands, &mut T) + 'static> From<F> for System<T> {
+    fn from(run: F) -> Self {
+        Self::new(run)
+    }
+}
+
+impl<T> std::fmt::Debug for System<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("System")
+            .field("label", &self.label)
+            .field("before", &self.before)
+            .field("after", &self.after)
+            .finish()
+    }
+}
+
+pub(crate) struct Schedule<T> {
+    stages:   HashMap<Stage, Vec<System<T>>>,
+    unsorted: HashSet<Stage>,
+    disabled: HashSet<String>,
+}
+
+impl<T> Schedule<T> {
+    pub(crate) fn new() -> Self {
+        Self { stages: HashMap::new(), unsorted: HashSet::new(), disabled: HashSet::new() }
+    }
+
+    pub(crate) fn add(&mut self, stage: Stage, system: System<T>) {
+        self.stages.entry(stage).or_default().push(system);
+        self.unsorted.insert(stage);
+    }
+
+    pub(crate) fn set_enabled(&mut self, label: &str, enabled: bool) {
+        if enabled {
+            self.disabled.remove(label);
+        } else {
+            self.disabled.insert(label.to_string());
+        }
+    }
+
+    pub(crate) fn is_enabled(&self, label: &str) -> bool {
+        !self.disabled.contains(label)
+    }
+
+    /// Takes the systems of a stage in the order they have to run in, they have to be given back
+    /// with [`Schedule::restore`].
+    pub(crate) fn take(&mut self, stage: Stage) -> Vec<System<T>> {
+        let systems = self.stages.remove(&stage).unwrap_or_default();
+        if self.unsorted.remove(&stage) { sort(stage, systems) } else { systems }
+    }
+
+    pub(crate) fn restore(&mut self, stage: Stage, systems: Vec<System<T>>) {
+        self.stages.insert(stage, systems);
+    }
+
+    /// Whether the system is not disabled through its label.
+    pub(crate) fn should_run(&self, system: &System<T>) -> bool {
+        system.label().is_none_or(|label| self.is_enabled(label))
+    }
+}
+
+impl<T> std::fmt::Debug for Schedule<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Schedule").field("stages", &self.stages).field("disabled", &self.disabled).finish()
+    }
+}
+
+// ---------------< PRIVATE >---------------
+
+/// Orders systems by their `before` and `after` constraints, otherwise keeping the insertion order.
+fn sort<T>(stage: Stage, systems: Vec<System<T>>) -> Vec<System<T>> {
+    let mut edges = vec![Vec::new(); systems.len()];
+    let mut in_degree = vec![0; systems.len()];
+
+    let labeled = |label: &str| -> Vec<usize> {
+        let indices: Vec<usize> =
+            systems.iter().enumerate().filter(|(_, s)| s.label() == Some(label)).map(|(i, _)| i).collect();
+        if indices.is_empty() {
+            warn!("No system labeled '{label}' in stage {stage:?}");
+        }
+        indices
+    };
+
+    for (i, system) in systems.iter().enumerate() {
+        for j in system.before.iter().flat_map(|label| labeled(label)) {
+            edges[i].push(j);
+            in_degree[j] += 1;
+        }
+        for j in system.after.iter().flat_map(|label| labeled(label)) {
+            edges[j].push(i);
+            in_degree[i] += 1;
+        }
+    }
+
+    let mut ready: BTreeSet<usize> = (0..systems.len()).filter(|&i| in_degree[i] == 0).collect();
+    let mut order = Vec::with_capacity(systems.len());
+
+    while let Some(i) = ready.pop_first() {
+        order.push(i);
+        for &j in &edges[i] {
+            in_degree[j] -= 1;
+            if in_degree[j] == 0 {
+                ready.insert(j);
+            }
+        }
+    }
+
+    if order.len() != systems.len() {
+        error!("Systems in stage {stage:?} have cyclic ordering constraints, using insertion order");
+        return systems;
+    }
+
+    let mut systems: Vec<Option<System<T>>> = systems.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| systems[i].take()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system(label: &str) -> System<()> {
+        System::new(|_, _| {}).with_label(label)
+    }
+
+    fn labels(systems: &[System<()>]) -> Vec<&str> {
+        systems.iter().map(|system| system.label().unwrap_or_default()).collect()
+    }
+
+    #[test]
+    fn before_and_after() {
+        let systems = vec![
+            system("physics"),
+            system("render").after("physics"),
+            system("debug").after("input"),
+            system("input").before("physics"),
+            system("audio"),
+        ];
+        let sorted = sort(Stage::Update, systems);
+
+        assert_eq!(labels(&sorted), ["input", "physics", "render", "debug", "audio"]);
+    }
+
+    #[test]
+    fn shared_labels_are_ordered_together() {
+        let systems =
+            vec![system("draw"), system("draw"), system("clear").before("draw"), system("present").after("draw")];
+        let sorted = sort(Stage::PreRender, systems);
+
+        assert_eq!(labels(&sorted), ["clear", "draw", "draw", "present"]);
+    }
+
+    #[test]
+    fn missing_label_is_ignored() {
+        let systems = vec![system("a").after("missing"), system("b").before("missing"), system("c")];
+        let sorted = sort(Stage::Update, systems);
+
+        assert_eq!(labels(&sorted), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn cycle_keeps_insertion_order() {
+        let systems = vec![system("c").after("b"), system("a").after("c"), system("b").after("a"), system("d")];
+        let sorted = sort(Stage::Update, systems);
+
+        assert_eq!(labels(&sorted), ["c", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn schedule_sorts_each_stage_once_and_skips_disabled_labels() {
+        let mut schedule = Schedule::new();
+        schedule.add(Stage::Update, system("b").after("a"));
+        schedule.add(Stage::Update, system("a"));
+        schedule.add(Stage::Update, System::new(|_, _| {}));
+        schedule.add(Stage::Fixed, system("fixed"));
+
+        let systems = schedule.take(Stage::Update);
+        assert_eq!(labels(&systems), ["a", "b", ""]);
+        schedule.restore(Stage::Update, systems);
+        assert_eq!(labels(&schedule.take(Stage::Update)), ["a", "b", ""]);
+        assert_eq!(labels(&schedule.take(Stage::Fixed)), ["fixed"]);
+        assert!(schedule.take(Stage::PreRender).is_empty());
+
+        schedule.set_enabled("a", false);
+        assert!(!schedule.should_run(&system("a")));
+        assert!(schedule.should_run(&system("b")));
+        assert!(schedule.should_run(&System::new(|_, _| {})));
+
+        schedule.set_enabled("a", true);
+        assert!(schedule.is_enabled("a"));
+    }
+}
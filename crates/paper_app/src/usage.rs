@@ -0,0 +1,52 @@
+use std::hash::Hash;
+
+use hashbrown::{HashMap, HashSet};
+
+/// Counts the entities using a mesh or material, so removing one that is still in use can be deferred
+/// until its last user is gone.
+#[derive(Debug)]
+pub(crate) struct Usage<K> {
+    users:   HashMap<K, usize>,
+    pending: HashSet<K>,
+}
+
+impl<K: Copy + Eq + Hash> Usage<K> {
+    pub(crate) fn new() -> Self {
+        Self { users: HashMap::new(), pending: HashSet::new() }
+    }
+
+    pub(crate) fn users(&self, key: &K) -> usize {
+        self.users.get(key).copied().unwrap_or(0)
+    }
+
+    pub(crate) fn acquire(&mut self, key: K) {
+        *self.users.entry(key).or_insert(0) += 1;
+    }
+
+    /// Returns `true` if `key` was waiting for removal and has no users left.
+    pub(crate) fn release(&mut self, key: K) -> bool {
+        let Some(users) = self.users.get_mut(&key) else {
+            return false;
+        };
+        *users -= 1;
+        if *users > 0 {
+            return false;
+        }
+        self.users.remove(&key);
+        self.pending.remove(&key)
+    }
+
+    /// Returns `true` if `key` can be removed right away, otherwise it is removed with its last user.
+    pub(crate) fn request_removal(&mut self, key: K) -> bool {
+        if self.users(&key) > 0 {
+            self.pending.insert(key);
+            return false;
+        }
+        self.pending.remove(&key);
+        true
+    }
+
+    pub(crate) fn cancel_removal(&mut self, key: &K) {
+        self.pending.remove(key);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::Resources;
+
+/// Double buffered queue of user events of type `E`, stored as a resource.
+///
+/// Events sent during a frame can be read during the whole next frame, independent of the order
+/// systems and callbacks run in.
+#[derive(Debug, Clone)]
+pub struct UserEvents<E> {
+    previous: Vec<E>,
+    current:  Vec<E>,
+}
+
+impl<E> UserEvents<E> {
+    pub fn send(&mut self, event: E) {
+        self.current.push(event);
+    }
+
+    /// The events sent during the previous frame.
+    pub fn read(&self) -> &[E] {
+        &self.previous
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_empty()
+    }
+
+    pub(crate) fn update(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+}
+
+impl<E: 'static> UserEvents<E> {
+    pub(crate) fn update_resource(resources: &mut Resources) {
+        if let Some(events) = resources.get_mut::<Self>() {
+            events.update();
+        }
+    }
+}
+
+impl<E> Default for UserEvents<E> {
+    fn default() -> Self {
+        Self { previous: Vec::new(), current: Vec::new() }
+    }
+}
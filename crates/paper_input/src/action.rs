@@ -1,3 +1,7 @@
+use std::{fmt, str::FromStr};
+
+use crate::ParseInputError;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Action {
     Release,
@@ -14,3 +18,22 @@ impl From<glfw::Action> for Action {
         }
     }
 }
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl FromStr for Action {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Release" => Ok(Self::Release),
+            "Press" => Ok(Self::Press),
+            "Repeat" => Ok(Self::Repeat),
+            _ => Err(ParseInputError::new("action", s)),
+        }
+    }
+}
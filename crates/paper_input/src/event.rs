@@ -1,46 +1,221 @@
+use std::{fmt, path::PathBuf, str::FromStr};
+
 use log::warn;
 
-use crate::{Action, Key, MouseButton};
+use crate::{Action, Gamepad, GamepadButton, Key, Modifiers, MouseButton, ParseInputError};
+
+const LIST_SEPARATOR: char = ',';
+const PATH_SEPARATOR: char = '\t';
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Event {
     // Basic Events
-    Key(Key, Action),
-    MouseMove(u64, u64),
+    /// `scancode` is platform specific and mostly useful for [`Key::Unknown`].
+    Key {
+        key:       Key,
+        action:    Action,
+        modifiers: Modifiers,
+        scancode:  i32,
+    },
+    Text(char),
+    /// Cursor position in logical pixels.
+    MouseMove(f64, f64),
     MouseButton(MouseButton, Action),
-    MouseScroll(u64, u64),
+    MouseScroll(f64, f64),
     MouseEnter(bool),
+    /// New framebuffer size in physical pixels.
     Resize(u32, u32),
     Focus(bool),
     Close,
     Refresh,
     Maximize(bool),
     Iconify(bool),
-    ContentScale(u32, u32),
+    /// The scale factor changed, e.g. because the window moved to another monitor.
+    ContentScale(f32, f32),
     FileDrop(Vec<std::path::PathBuf>),
     WindowPos(i32, i32),
     WindowSize(i32, i32),
+    GamepadConnected(Gamepad),
+    GamepadDisconnected(Gamepad),
+    GamepadButton(Gamepad, GamepadButton, Action),
 
     // Grouped Events
     AnyKey(Vec<Key>, Action),
     AnyMouseButton(Vec<MouseButton>, Action),
 }
 
-impl From<glfw::WindowEvent> for Event {
-    fn from(event: glfw::WindowEvent) -> Self {
-        match event {
-            glfw::WindowEvent::Key(key, _, action, _) => {
-                let key = key.into();
-                let action = action.into();
-                Self::Key(key, action)
+impl Event {
+    pub fn key(key: Key, action: Action) -> Self {
+        Self::Key { key, action, modifiers: Modifiers::NONE, scancode: 0 }
+    }
+
+    pub fn key_with_modifiers(key: Key, action: Action, modifiers: Modifiers) -> Self {
+        Self::Key { key, action, modifiers, scancode: 0 }
+    }
+
+    /// The [`EventKind`] callbacks are matched against, `None` for grouped events.
+    pub fn kind(&self) -> Option<EventKind> {
+        Some(match *self {
+            Event::Key { key, action, modifiers, .. } => EventKind::Key(key, action, modifiers),
+            Event::Text(_) => EventKind::Text,
+            Event::MouseMove(..) => EventKind::MouseMove,
+            Event::MouseButton(button, action) => EventKind::MouseButton(button, action),
+            Event::MouseScroll(..) => EventKind::MouseScroll,
+            Event::MouseEnter(entered) => EventKind::MouseEnter(entered),
+            Event::Resize(..) => EventKind::Resize,
+            Event::Focus(focused) => EventKind::Focus(focused),
+            Event::Close => EventKind::Close,
+            Event::Refresh => EventKind::Refresh,
+            Event::Maximize(maximized) => EventKind::Maximize(maximized),
+            Event::Iconify(iconified) => EventKind::Iconify(iconified),
+            Event::ContentScale(..) => EventKind::ContentScale,
+            Event::FileDrop(_) => EventKind::FileDrop,
+            Event::WindowPos(..) => EventKind::WindowPos,
+            Event::WindowSize(..) => EventKind::WindowSize,
+            Event::GamepadConnected(_) => EventKind::GamepadConnected,
+            Event::GamepadDisconnected(_) => EventKind::GamepadDisconnected,
+            Event::GamepadButton(_, button, action) => EventKind::GamepadButton(button, action),
+            Event::AnyKey(..) | Event::AnyMouseButton(..) => return None,
+        })
+    }
+}
+
+/// Written as the variant name followed by its payload, e.g. `Key Space Press None 57`.
+///
+/// Characters are written as their code point, the paths of a `FileDrop` are separated by tabs.
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Key { key, action, modifiers, scancode } => write!(f, "Key {key} {action} {modifiers} {scancode}"),
+            Event::Text(c) => write!(f, "Text {}", *c as u32),
+            Event::MouseMove(x, y) => write!(f, "MouseMove {x} {y}"),
+            Event::MouseButton(button, action) => write!(f, "MouseButton {button} {action}"),
+            Event::MouseScroll(x, y) => write!(f, "MouseScroll {x} {y}"),
+            Event::MouseEnter(entered) => write!(f, "MouseEnter {entered}"),
+            Event::Resize(width, height) => write!(f, "Resize {width} {height}"),
+            Event::Focus(focused) => write!(f, "Focus {focused}"),
+            Event::Close => write!(f, "Close"),
+            Event::Refresh => write!(f, "Refresh"),
+            Event::Maximize(maximized) => write!(f, "Maximize {maximized}"),
+            Event::Iconify(iconified) => write!(f, "Iconify {iconified}"),
+            Event::ContentScale(x, y) => write!(f, "ContentScale {x} {y}"),
+            Event::FileDrop(paths) => {
+                write!(f, "FileDrop")?;
+                paths.iter().try_for_each(|path| write!(f, "{PATH_SEPARATOR}{}", path.display()))
+            }
+            Event::WindowPos(x, y) => write!(f, "WindowPos {x} {y}"),
+            Event::WindowSize(width, height) => write!(f, "WindowSize {width} {height}"),
+            Event::GamepadConnected(gamepad) => write!(f, "GamepadConnected {}", gamepad.id()),
+            Event::GamepadDisconnected(gamepad) => write!(f, "GamepadDisconnected {}", gamepad.id()),
+            Event::GamepadButton(gamepad, button, action) => {
+                write!(f, "GamepadButton {} {button} {action}", gamepad.id())
+            }
+            Event::AnyKey(keys, action) => write!(f, "AnyKey {action} {}", join(keys)),
+            Event::AnyMouseButton(buttons, action) => write!(f, "AnyMouseButton {action} {}", join(buttons)),
+        }
+    }
+}
+
+impl FromStr for Event {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(paths) = s.strip_prefix("FileDrop") {
+            return Ok(Self::FileDrop(
+                paths.split(PATH_SEPARATOR).filter(|p| !p.is_empty()).map(PathBuf::from).collect(),
+            ));
+        }
+
+        Ok(match s.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["Key", key, action, modifiers, scancode] => Self::Key {
+                key:       value(key, s)?,
+                action:    value(action, s)?,
+                modifiers: value(modifiers, s)?,
+                scancode:  value(scancode, s)?,
+            },
+            ["Text", code] => {
+                Self::Text(char::from_u32(value(code, s)?).ok_or_else(|| ParseInputError::new("event", s))?)
+            }
+            ["MouseMove", x, y] => Self::MouseMove(value(x, s)?, value(y, s)?),
+            ["MouseButton", button, action] => Self::MouseButton(value(button, s)?, value(action, s)?),
+            ["MouseScroll", x, y] => Self::MouseScroll(value(x, s)?, value(y, s)?),
+            ["MouseEnter", entered] => Self::MouseEnter(value(entered, s)?),
+            ["Resize", width, height] => Self::Resize(value(width, s)?, value(height, s)?),
+            ["Focus", focused] => Self::Focus(value(focused, s)?),
+            ["Close"] => Self::Close,
+            ["Refresh"] => Self::Refresh,
+            ["Maximize", maximized] => Self::Maximize(value(maximized, s)?),
+            ["Iconify", iconified] => Self::Iconify(value(iconified, s)?),
+            ["ContentScale", x, y] => Self::ContentScale(value(x, s)?, value(y, s)?),
+            ["WindowPos", x, y] => Self::WindowPos(value(x, s)?, value(y, s)?),
+            ["WindowSize", width, height] => Self::WindowSize(value(width, s)?, value(height, s)?),
+            ["GamepadConnected", id] => Self::GamepadConnected(Gamepad(value(id, s)?)),
+            ["GamepadDisconnected", id] => Self::GamepadDisconnected(Gamepad(value(id, s)?)),
+            ["GamepadButton", id, button, action] => {
+                Self::GamepadButton(Gamepad(value(id, s)?), value(button, s)?, value(action, s)?)
+            }
+            ["AnyKey", action, keys @ ..] => Self::AnyKey(list(keys, s)?, value(action, s)?),
+            ["AnyMouseButton", action, buttons @ ..] => Self::AnyMouseButton(list(buttons, s)?, value(action, s)?),
+            _ => return Err(ParseInputError::new("event", s)),
+        })
+    }
+}
+
+/// Identifies an [`Event`] without its payload so it can be used to match callbacks.
+///
+/// Scancodes, positions, sizes and the gamepad an event originates from are not part of the kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    Key(Key, Action, Modifiers),
+    Text,
+    MouseMove,
+    MouseButton(MouseButton, Action),
+    MouseScroll,
+    MouseEnter(bool),
+    Resize,
+    Focus(bool),
+    Close,
+    Refresh,
+    Maximize(bool),
+    Iconify(bool),
+    ContentScale,
+    FileDrop,
+    WindowPos,
+    WindowSize,
+    GamepadConnected,
+    GamepadDisconnected,
+    GamepadButton(GamepadButton, Action),
+}
+
+impl EventKind {
+    /// Key callbacks registered without modifiers fire regardless of the modifiers held, so
+    /// `Key::S.press()` also triggers on `Ctrl+S`. This returns that fallback kind, if any.
+    pub fn without_modifiers(&self) -> Option<EventKind> {
+        match *self {
+            EventKind::Key(key, action, modifiers) if !modifiers.is_empty() => {
+                Some(EventKind::Key(key, action, Modifiers::NONE))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<glfw::WindowEvent> for Event {
+    type Error = glfw::WindowEvent;
+
+    fn try_from(event: glfw::WindowEvent) -> Result<Self, Self::Error> {
+        Ok(match event {
+            glfw::WindowEvent::Key(key, scancode, action, modifiers) => {
+                Self::Key { key: key.into(), action: action.into(), modifiers: modifiers.into(), scancode }
             }
+            glfw::WindowEvent::Char(c) => Self::Text(c),
             glfw::WindowEvent::MouseButton(button, action, _) => {
                 let button = button.into();
                 let action = action.into();
                 Self::MouseButton(button, action)
             }
-            glfw::WindowEvent::CursorPos(x, y) => Self::MouseMove(x as u64, y as u64),
-            glfw::WindowEvent::Scroll(x, y) => Self::MouseScroll(x as u64, y as u64),
+            glfw::WindowEvent::CursorPos(x, y) => Self::MouseMove(x, y),
+            glfw::WindowEvent::Scroll(x, y) => Self::MouseScroll(x, y),
             glfw::WindowEvent::CursorEnter(entered) => Self::MouseEnter(entered),
             glfw::WindowEvent::FramebufferSize(width, height) => Self::Resize(width as u32, height as u32),
             glfw::WindowEvent::Focus(focused) => Self::Focus(focused),
@@ -48,14 +223,32 @@ impl From<glfw::WindowEvent> for Event {
             glfw::WindowEvent::Refresh => Self::Refresh,
             glfw::WindowEvent::Maximize(maximized) => Self::Maximize(maximized),
             glfw::WindowEvent::Iconify(iconified) => Self::Iconify(iconified),
-            glfw::WindowEvent::ContentScale(x, y) => Self::ContentScale(x as u32, y as u32),
+            glfw::WindowEvent::ContentScale(x, y) => Self::ContentScale(x, y),
             glfw::WindowEvent::FileDrop(paths) => Self::FileDrop(paths),
             glfw::WindowEvent::Pos(x, y) => Self::WindowPos(x, y),
             glfw::WindowEvent::Size(width, height) => Self::WindowSize(width, height),
             _ => {
                 warn!("Unhandled window event: {event:?}");
-                Self::Close
+                return Err(event);
             }
-        }
+        })
+    }
+}
+
+// ---------------< PRIVATE >---------------
+
+fn join<T: fmt::Display>(values: &[T]) -> String {
+    values.iter().map(T::to_string).collect::<Vec<_>>().join(&LIST_SEPARATOR.to_string())
+}
+
+fn value<T: FromStr>(value: &str, event: &str) -> Result<T, ParseInputError> {
+    value.parse().map_err(|_| ParseInputError::new("event", event))
+}
+
+fn list<T: FromStr>(values: &[&str], event: &str) -> Result<Vec<T>, ParseInputError> {
+    match values {
+        [] => Ok(Vec::new()),
+        [values] => values.split(LIST_SEPARATOR).map(|v| value(v, event)).collect(),
+        _ => Err(ParseInputError::new("event", event)),
     }
 }
@@ -0,0 +1,471 @@
+use std::{cell::RefCell, collections::BTreeMap, fmt, rc::Rc, str::FromStr};
+
+use crate::{Action, ButtonInput, Event, ParseInputError};
+
+pub const GAMEPAD_BUTTON_COUNT: usize = GamepadButton::ALL.len();
+pub const GAMEPAD_AXIS_COUNT: usize = GamepadAxis::ALL.len();
+
+const MAX_GAMEPADS: i32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Gamepad(pub u8);
+
+impl Gamepad {
+    pub fn id(&self) -> u8 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    LeftBumper,
+    RightBumper,
+    Back,
+    Start,
+    Guide,
+    LeftThumb,
+    RightThumb,
+    DpadUp,
+    DpadRight,
+    DpadDown,
+    DpadLeft,
+}
+
+impl GamepadButton {
+    #[rustfmt::skip]
+    pub const ALL: [GamepadButton; 15] = [
+        GamepadButton::A, GamepadButton::B, GamepadButton::X, GamepadButton::Y,
+        GamepadButton::LeftBumper, GamepadButton::RightBumper,
+        GamepadButton::Back, GamepadButton::Start, GamepadButton::Guide,
+        GamepadButton::LeftThumb, GamepadButton::RightThumb,
+        GamepadButton::DpadUp, GamepadButton::DpadRight, GamepadButton::DpadDown, GamepadButton::DpadLeft,
+    ];
+
+    pub fn press(self, gamepad: Gamepad) -> Event {
+        Event::GamepadButton(gamepad, self, Action::Press)
+    }
+
+    pub fn release(self, gamepad: Gamepad) -> Event {
+        Event::GamepadButton(gamepad, self, Action::Release)
+    }
+
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+
+    fn glfw(self) -> glfw::GamepadButton {
+        match self {
+            GamepadButton::A => glfw::GamepadButton::ButtonA,
+            GamepadButton::B => glfw::GamepadButton::ButtonB,
+            GamepadButton::X => glfw::GamepadButton::ButtonX,
+            GamepadButton::Y => glfw::GamepadButton::ButtonY,
+            GamepadButton::LeftBumper => glfw::GamepadButton::ButtonLeftBumper,
+            GamepadButton::RightBumper => glfw::GamepadButton::ButtonRightBumper,
+            GamepadButton::Back => glfw::GamepadButton::ButtonBack,
+            GamepadButton::Start => glfw::GamepadButton::ButtonStart,
+            GamepadButton::Guide => glfw::GamepadButton::ButtonGuide,
+            GamepadButton::LeftThumb => glfw::GamepadButton::ButtonLeftThumb,
+            GamepadButton::RightThumb => glfw::GamepadButton::ButtonRightThumb,
+            GamepadButton::DpadUp => glfw::GamepadButton::ButtonDpadUp,
+            GamepadButton::DpadRight => glfw::GamepadButton::ButtonDpadRight,
+            GamepadButton::DpadDown => glfw::GamepadButton::ButtonDpadDown,
+            GamepadButton::DpadLeft => glfw::GamepadButton::ButtonDpadLeft,
+        }
+    }
+}
+
+impl fmt::Display for GamepadButton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl FromStr for GamepadButton {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|button| button.to_string() == s)
+            .ok_or_else(|| ParseInputError::new("gamepad button", s))
+    }
+}
+
+/// Sticks report values in `-1.0..=1.0`, triggers in `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    pub const ALL: [GamepadAxis; 6] = [
+        GamepadAxis::LeftX,
+        GamepadAxis::LeftY,
+        GamepadAxis::RightX,
+        GamepadAxis::RightY,
+        GamepadAxis::LeftTrigger,
+        GamepadAxis::RightTrigger,
+    ];
+
+    pub fn is_trigger(&self) -> bool {
+        matches!(self, GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger)
+    }
+
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+
+    fn glfw(self) -> glfw::GamepadAxis {
+        match self {
+            GamepadAxis::LeftX => glfw::GamepadAxis::AxisLeftX,
+            GamepadAxis::LeftY => glfw::GamepadAxis::AxisLeftY,
+            GamepadAxis::RightX => glfw::GamepadAxis::AxisRightX,
+            GamepadAxis::RightY => glfw::GamepadAxis::AxisRightY,
+            GamepadAxis::LeftTrigger => glfw::GamepadAxis::AxisLeftTrigger,
+            GamepadAxis::RightTrigger => glfw::GamepadAxis::AxisRightTrigger,
+        }
+    }
+}
+
+impl fmt::Display for GamepadAxis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl FromStr for GamepadAxis {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|axis| axis.to_string() == s).ok_or_else(|| ParseInputError::new("gamepad axis", s))
+    }
+}
+
+/// Raw state of a connected gamepad as reported by a [`GamepadSource`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GamepadSnapshot {
+    pub name:    String,
+    pub buttons: [bool; GAMEPAD_BUTTON_COUNT],
+    pub axes:    [f32; GAMEPAD_AXIS_COUNT],
+}
+
+impl GamepadSnapshot {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), ..Default::default() }
+    }
+
+    pub fn with_button(mut self, button: GamepadButton, pressed: bool) -> Self {
+        self.set_button(button, pressed);
+        self
+    }
+
+    pub fn with_axis(mut self, axis: GamepadAxis, value: f32) -> Self {
+        self.set_axis(axis, value);
+        self
+    }
+
+    pub fn set_button(&mut self, button: GamepadButton, pressed: bool) {
+        self.buttons[button.index()] = pressed;
+    }
+
+    pub fn set_axis(&mut self, axis: GamepadAxis, value: f32) {
+        self.axes[axis.index()] = value;
+    }
+
+    pub fn button(&self, button: GamepadButton) -> bool {
+        self.buttons[button.index()]
+    }
+
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes[axis.index()]
+    }
+}
+
+/// Provides the gamepads that are currently connected.
+///
+/// [`GlfwGamepadSource`] reads real hardware, [`VirtualGamepadSource`] can be driven from code.
+pub trait GamepadSource {
+    fn poll(&mut self) -> Vec<(Gamepad, GamepadSnapshot)>;
+
+    /// Adds SDL `gamecontrollerdb.txt` style mappings. Returns `false` if they could not be applied.
+    fn add_mappings(&mut self, _mappings: &str) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GlfwGamepadSource {
+    glfw: glfw::Glfw,
+}
+
+impl GlfwGamepadSource {
+    pub fn new(glfw: glfw::Glfw) -> Self {
+        Self { glfw }
+    }
+}
+
+impl GamepadSource for GlfwGamepadSource {
+    fn poll(&mut self) -> Vec<(Gamepad, GamepadSnapshot)> {
+        (0..MAX_GAMEPADS)
+            .filter_map(|id| {
+                let joystick = self.glfw.get_joystick(glfw::JoystickId::from_i32(id)?);
+                if !joystick.is_present() || !joystick.is_gamepad() {
+                    return None;
+                }
+
+                let state = joystick.get_gamepad_state()?;
+                let mut snapshot = GamepadSnapshot::new(&joystick.get_gamepad_name().unwrap_or_default());
+
+                for button in GamepadButton::ALL {
+                    snapshot.set_button(button, state.get_button_state(button.glfw()) != glfw::Action::Release);
+                }
+
+                for axis in GamepadAxis::ALL {
+                    let value = state.get_axis(axis.glfw());
+                    snapshot.set_axis(axis, if axis.is_trigger() { trigger_from_glfw(value) } else { value });
+                }
+
+                Some((Gamepad(id as u8), snapshot))
+            })
+            .collect()
+    }
+
+    fn add_mappings(&mut self, mappings: &str) -> bool {
+        self.glfw.update_gamepad_mappings(mappings)
+    }
+}
+
+/// A [`GamepadSource`] driven from code, e.g. for tests or on-screen controls.
+///
+/// Clones share the same gamepads, so one handle can be handed to the application while another
+/// one is kept to connect gamepads and change their state.
+#[derive(Debug, Clone, Default)]
+pub struct VirtualGamepadSource {
+    gamepads: Rc<RefCell<BTreeMap<Gamepad, GamepadSnapshot>>>,
+}
+
+impl VirtualGamepadSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn connect(&self, gamepad: Gamepad, snapshot: GamepadSnapshot) {
+        self.gamepads.borrow_mut().insert(gamepad, snapshot);
+    }
+
+    pub fn disconnect(&self, gamepad: Gamepad) {
+        self.gamepads.borrow_mut().remove(&gamepad);
+    }
+
+    pub fn set_button(&self, gamepad: Gamepad, button: GamepadButton, pressed: bool) {
+        if let Some(snapshot) = self.gamepads.borrow_mut().get_mut(&gamepad) {
+            snapshot.set_button(button, pressed);
+        }
+    }
+
+    pub fn set_axis(&self, gamepad: Gamepad, axis: GamepadAxis, value: f32) {
+        if let Some(snapshot) = self.gamepads.borrow_mut().get_mut(&gamepad) {
+            snapshot.set_axis(axis, value);
+        }
+    }
+}
+
+impl GamepadSource for VirtualGamepadSource {
+    fn poll(&mut self) -> Vec<(Gamepad, GamepadSnapshot)> {
+        self.gamepads.borrow().iter().map(|(gamepad, snapshot)| (*gamepad, snapshot.clone())).collect()
+    }
+}
+
+/// Tracked state of a connected gamepad, with the deadzone already applied to its axes.
+#[derive(Debug, Clone, Default)]
+pub struct GamepadState {
+    name:    String,
+    buttons: ButtonInput<GamepadButton>,
+    axes:    [f32; GAMEPAD_AXIS_COUNT],
+}
+
+impl GamepadState {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn buttons(&self) -> &ButtonInput<GamepadButton> {
+        &self.buttons
+    }
+
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes[axis.index()]
+    }
+
+    pub(crate) fn buttons_mut(&mut self) -> &mut ButtonInput<GamepadButton> {
+        &mut self.buttons
+    }
+
+    pub(crate) fn update(&mut self, gamepad: Gamepad, snapshot: GamepadSnapshot, deadzone: f32) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        for button in GamepadButton::ALL {
+            match (snapshot.button(button), self.buttons.pressed(button)) {
+                (true, false) => {
+                    self.buttons.press(button);
+                    events.push(button.press(gamepad));
+                }
+                (false, true) => {
+                    self.buttons.release(button);
+                    events.push(button.release(gamepad));
+                }
+                _ => {}
+            }
+        }
+
+        for axis in GamepadAxis::ALL {
+            self.axes[axis.index()] = apply_deadzone(snapshot.axis(axis), deadzone);
+        }
+
+        self.name = snapshot.name;
+
+        events
+    }
+}
+
+/// GLFW reports triggers in `-1.0..=1.0` like the sticks, at rest they are at `-1.0`.
+fn trigger_from_glfw(value: f32) -> f32 {
+    ((value + 1.0) * 0.5).clamp(0.0, 1.0)
+}
+
+fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    if value.abs() <= deadzone {
+        return 0.0;
+    }
+    let scaled = (value.abs() - deadzone) / (1.0 - deadzone);
+    scaled.min(1.0).copysign(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AxisBinding, InputMap, InputState};
+
+    const PAD: Gamepad = Gamepad(0);
+
+    fn update(state: &mut InputState, source: &mut VirtualGamepadSource) -> Vec<Event> {
+        state.end_frame();
+        state.update_gamepads(source.poll())
+    }
+
+    #[test]
+    fn connect_and_disconnect_events() {
+        let mut state = InputState::new();
+        let mut source = VirtualGamepadSource::new();
+        let handle = source.clone();
+
+        assert!(update(&mut state, &mut source).is_empty());
+
+        handle.connect(PAD, GamepadSnapshot::new("Pad"));
+        handle.connect(Gamepad(3), GamepadSnapshot::new("Other"));
+        assert_eq!(
+            update(&mut state, &mut source),
+            [Event::GamepadConnected(PAD), Event::GamepadConnected(Gamepad(3))]
+        );
+        assert_eq!(state.gamepad(PAD).map(GamepadState::name), Some("Pad"));
+        assert_eq!(state.gamepads().count(), 2);
+
+        // Staying connected does not repeat the event
+        assert!(update(&mut state, &mut source).is_empty());
+
+        handle.disconnect(PAD);
+        assert_eq!(update(&mut state, &mut source), [Event::GamepadDisconnected(PAD)]);
+        assert!(state.gamepad(PAD).is_none());
+        assert_eq!(state.gamepads().collect::<Vec<_>>(), [Gamepad(3)]);
+    }
+
+    #[test]
+    fn just_pressed_and_just_released_buttons() {
+        let mut state = InputState::new();
+        let mut source = VirtualGamepadSource::new();
+        source.connect(PAD, GamepadSnapshot::new("Pad").with_button(GamepadButton::B, true));
+
+        // Buttons held while connecting are pressed in the first frame
+        let events = update(&mut state, &mut source);
+        assert_eq!(events, [Event::GamepadConnected(PAD), GamepadButton::B.press(PAD)]);
+        assert!(state.gamepad_button_just_pressed(PAD, GamepadButton::B));
+
+        source.set_button(PAD, GamepadButton::A, true);
+        assert_eq!(update(&mut state, &mut source), [GamepadButton::A.press(PAD)]);
+        assert!(state.gamepad_button_just_pressed(PAD, GamepadButton::A));
+        assert!(state.any_gamepad_button_just_pressed(GamepadButton::A));
+        assert!(!state.gamepad_button_just_pressed(PAD, GamepadButton::B));
+        assert!(state.gamepad_button_pressed(PAD, GamepadButton::B));
+
+        assert!(update(&mut state, &mut source).is_empty());
+        assert!(state.gamepad_button_pressed(PAD, GamepadButton::A));
+        assert!(!state.gamepad_button_just_pressed(PAD, GamepadButton::A));
+
+        source.set_button(PAD, GamepadButton::A, false);
+        assert_eq!(update(&mut state, &mut source), [GamepadButton::A.release(PAD)]);
+        assert!(state.gamepad_button_just_released(PAD, GamepadButton::A));
+        assert!(!state.gamepad_button_pressed(PAD, GamepadButton::A));
+
+        update(&mut state, &mut source);
+        assert!(!state.gamepad_button_just_released(PAD, GamepadButton::A));
+    }
+
+    #[test]
+    fn deadzone_rescales_axes() {
+        let mut state = InputState::new();
+        state.set_gamepad_deadzone(0.2);
+        let mut source = VirtualGamepadSource::new();
+        source.connect(PAD, GamepadSnapshot::new("Pad"));
+
+        let mut axis = |value: f32| {
+            source.set_axis(PAD, GamepadAxis::LeftX, value);
+            update(&mut state, &mut source);
+            state.gamepad_axis(PAD, GamepadAxis::LeftX)
+        };
+
+        assert_eq!(axis(0.15), 0.0);
+        assert_eq!(axis(-0.2), 0.0);
+        assert!((axis(0.6) - 0.5).abs() < 1e-6);
+        assert!((axis(-0.6) + 0.5).abs() < 1e-6);
+        assert_eq!(axis(1.0), 1.0);
+        assert_eq!(axis(-1.5), -1.0);
+
+        assert_eq!(state.gamepad_axis(Gamepad(7), GamepadAxis::LeftX), 0.0);
+
+        state.set_gamepad_deadzone(2.0);
+        assert_eq!(state.gamepad_deadzone(), 0.99);
+    }
+
+    #[test]
+    fn trigger_mapping() {
+        assert_eq!(trigger_from_glfw(-1.0), 0.0);
+        assert_eq!(trigger_from_glfw(0.0), 0.5);
+        assert_eq!(trigger_from_glfw(1.0), 1.0);
+        assert_eq!(trigger_from_glfw(-1.2), 0.0);
+
+        let mut state = InputState::new();
+        let mut source = VirtualGamepadSource::new();
+        source.connect(PAD, GamepadSnapshot::new("Pad").with_axis(GamepadAxis::RightTrigger, 0.05));
+        update(&mut state, &mut source);
+        assert_eq!(state.gamepad_axis(PAD, GamepadAxis::RightTrigger), 0.0);
+
+        source.set_axis(PAD, GamepadAxis::RightTrigger, 0.55);
+        update(&mut state, &mut source);
+        assert!((state.gamepad_axis(PAD, GamepadAxis::RightTrigger) - 0.5).abs() < 1e-6);
+
+        // Triggers drive axis bindings like any other axis
+        let mut map = InputMap::new();
+        map.bind_axis("accelerate", AxisBinding::Gamepad(GamepadAxis::RightTrigger));
+        assert!((map.axis("accelerate", &state) - 0.5).abs() < 1e-6);
+        assert_eq!(state.any_gamepad_axis(GamepadAxis::LeftTrigger), 0.0);
+    }
+}
@@ -0,0 +1,539 @@
+use std::{fmt, io, path::Path, str::FromStr};
+
+use hashbrown::HashMap;
+
+use crate::{GamepadAxis, GamepadButton, InputState, Key, MouseButton};
+
+const SOURCE_SEPARATOR: char = '.';
+const CHORD_SEPARATOR: char = '+';
+const AXIS_SEPARATOR: char = ',';
+const ALTERNATIVE_SEPARATOR: char = '|';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseInputError {
+    kind:  &'static str,
+    input: String,
+}
+
+impl ParseInputError {
+    pub(crate) fn new(kind: &'static str, input: &str) -> Self {
+        Self { kind, input: input.to_string() }
+    }
+}
+
+impl fmt::Display for ParseInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {}: '{}'", self.kind, self.input)
+    }
+}
+
+impl std::error::Error for ParseInputError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputSource {
+    Key(Key),
+    MouseButton(MouseButton),
+    /// A button on any connected gamepad.
+    GamepadButton(GamepadButton),
+}
+
+impl InputSource {
+    pub fn pressed(&self, state: &InputState) -> bool {
+        match *self {
+            InputSource::Key(key) => state.key_pressed(key),
+            InputSource::MouseButton(button) => state.mouse_button_pressed(button),
+            InputSource::GamepadButton(button) => state.any_gamepad_button_pressed(button),
+        }
+    }
+
+    pub fn just_pressed(&self, state: &InputState) -> bool {
+        match *self {
+            InputSource::Key(key) => state.key_just_pressed(key),
+            InputSource::MouseButton(button) => state.mouse_button_just_pressed(button),
+            InputSource::GamepadButton(button) => state.any_gamepad_button_just_pressed(button),
+        }
+    }
+
+    pub fn just_released(&self, state: &InputState) -> bool {
+        match *self {
+            InputSource::Key(key) => state.key_just_released(key),
+            InputSource::MouseButton(button) => state.mouse_button_just_released(button),
+            InputSource::GamepadButton(button) => state.any_gamepad_button_just_released(button),
+        }
+    }
+}
+
+impl From<Key> for InputSource {
+    fn from(key: Key) -> Self {
+        InputSource::Key(key)
+    }
+}
+
+impl From<MouseButton> for InputSource {
+    fn from(button: MouseButton) -> Self {
+        InputSource::MouseButton(button)
+    }
+}
+
+impl From<GamepadButton> for InputSource {
+    fn from(button: GamepadButton) -> Self {
+        InputSource::GamepadButton(button)
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Key(key) => write!(f, "Key{SOURCE_SEPARATOR}{key}"),
+            InputSource::MouseButton(button) => write!(f, "Mouse{SOURCE_SEPARATOR}{button}"),
+            InputSource::GamepadButton(button) => write!(f, "Gamepad{SOURCE_SEPARATOR}{button}"),
+        }
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.split_once(SOURCE_SEPARATOR) {
+            Some(("Key", key)) => Ok(InputSource::Key(key.parse()?)),
+            Some(("Mouse", button)) => Ok(InputSource::MouseButton(button.parse()?)),
+            Some(("Gamepad", button)) => Ok(InputSource::GamepadButton(button.parse()?)),
+            _ => Err(ParseInputError::new("input source", s)),
+        }
+    }
+}
+
+/// One or more inputs that have to be held at the same time (e.g. `Ctrl+S`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Binding {
+    inputs: Vec<InputSource>,
+}
+
+impl Binding {
+    pub fn new(input: impl Into<InputSource>) -> Self {
+        Self { inputs: vec![input.into()] }
+    }
+
+    pub fn chord<I: Into<InputSource>>(inputs: impl IntoIterator<Item = I>) -> Self {
+        Self { inputs: inputs.into_iter().map(Into::into).collect() }
+    }
+
+    pub fn with(mut self, input: impl Into<InputSource>) -> Self {
+        self.inputs.push(input.into());
+        self
+    }
+
+    pub fn inputs(&self) -> &[InputSource] {
+        &self.inputs
+    }
+
+    pub fn pressed(&self, state: &InputState) -> bool {
+        !self.inputs.is_empty() && self.inputs.iter().all(|input| input.pressed(state))
+    }
+
+    pub fn just_pressed(&self, state: &InputState) -> bool {
+        self.pressed(state) && self.inputs.iter().any(|input| input.just_pressed(state))
+    }
+
+    pub fn just_released(&self, state: &InputState) -> bool {
+        !self.pressed(state)
+            && self.inputs.iter().any(|input| input.just_released(state))
+            && self.inputs.iter().all(|input| input.pressed(state) || input.just_released(state))
+    }
+}
+
+impl<I: Into<InputSource>> From<I> for Binding {
+    fn from(input: I) -> Self {
+        Self::new(input)
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, input) in self.inputs.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {CHORD_SEPARATOR} ")?;
+            }
+            write!(f, "{input}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Binding {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let inputs = s.split(CHORD_SEPARATOR).map(str::parse).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { inputs })
+    }
+}
+
+/// Produces a value in `-1.0..=1.0`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AxisBinding {
+    /// Composes two bindings, e.g. `A`/`D`, into `-1.0`, `0.0` or `1.0`.
+    Buttons { negative: Binding, positive: Binding },
+    /// An analog axis of any connected gamepad.
+    Gamepad(GamepadAxis),
+}
+
+impl AxisBinding {
+    pub fn buttons(negative: impl Into<Binding>, positive: impl Into<Binding>) -> Self {
+        AxisBinding::Buttons { negative: negative.into(), positive: positive.into() }
+    }
+
+    pub fn value(&self, state: &InputState) -> f32 {
+        match self {
+            AxisBinding::Buttons { negative, positive } => {
+                let negative = if negative.pressed(state) { 1.0 } else { 0.0 };
+                let positive = if positive.pressed(state) { 1.0 } else { 0.0 };
+                positive - negative
+            }
+            AxisBinding::Gamepad(axis) => state.any_gamepad_axis(*axis),
+        }
+    }
+}
+
+impl From<GamepadAxis> for AxisBinding {
+    fn from(axis: GamepadAxis) -> Self {
+        AxisBinding::Gamepad(axis)
+    }
+}
+
+impl fmt::Display for AxisBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AxisBinding::Buttons { negative, positive } => write!(f, "{negative}{AXIS_SEPARATOR} {positive}"),
+            AxisBinding::Gamepad(axis) => write!(f, "GamepadAxis{SOURCE_SEPARATOR}{axis}"),
+        }
+    }
+}
+
+impl FromStr for AxisBinding {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((negative, positive)) = s.split_once(AXIS_SEPARATOR) {
+            return Ok(AxisBinding::Buttons { negative: negative.parse()?, positive: positive.parse()? });
+        }
+
+        match s.trim().split_once(SOURCE_SEPARATOR) {
+            Some(("GamepadAxis", axis)) => Ok(AxisBinding::Gamepad(axis.parse()?)),
+            _ => Err(ParseInputError::new("axis binding", s.trim())),
+        }
+    }
+}
+
+/// Maps named actions (e.g. `"jump"`, `"move_x"`) to rebindable inputs.
+///
+/// The map can be saved to and loaded from a plain text file with one action per line:
+///
+/// ```text
+/// button jump = Key.Space | Mouse.Left
+/// button save = Key.LeftControl + Key.S
+/// axis move_x = Key.A, Key.D | GamepadAxis.LeftX
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct InputMap {
+    buttons: HashMap<String, Vec<Binding>>,
+    axes:    HashMap<String, Vec<AxisBinding>>,
+}
+
+impl InputMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bind(&mut self, action: &str, binding: impl Into<Binding>) {
+        self.buttons.entry(action.to_string()).or_default().push(binding.into());
+    }
+
+    pub fn with_binding(mut self, action: &str, binding: impl Into<Binding>) -> Self {
+        self.bind(action, binding);
+        self
+    }
+
+    pub fn bind_axis(&mut self, action: &str, binding: impl Into<AxisBinding>) {
+        self.axes.entry(action.to_string()).or_default().push(binding.into());
+    }
+
+    pub fn with_axis(mut self, action: &str, binding: impl Into<AxisBinding>) -> Self {
+        self.bind_axis(action, binding);
+        self
+    }
+
+    pub fn bind_button_axis(&mut self, action: &str, negative: impl Into<Binding>, positive: impl Into<Binding>) {
+        self.bind_axis(action, AxisBinding::buttons(negative, positive));
+    }
+
+    pub fn with_button_axis(
+        mut self,
+        action: &str,
+        negative: impl Into<Binding>,
+        positive: impl Into<Binding>,
+    ) -> Self {
+        self.bind_button_axis(action, negative, positive);
+        self
+    }
+
+    /// Replaces every binding of `action` with `binding`.
+    pub fn rebind(&mut self, action: &str, binding: impl Into<Binding>) {
+        self.buttons.insert(action.to_string(), vec![binding.into()]);
+    }
+
+    /// Replaces every axis binding of `action` with `binding`.
+    pub fn rebind_axis(&mut self, action: &str, binding: impl Into<AxisBinding>) {
+        self.axes.insert(action.to_string(), vec![binding.into()]);
+    }
+
+    pub fn unbind(&mut self, action: &str, binding: &Binding) {
+        if let Some(bindings) = self.buttons.get_mut(action) {
+            bindings.retain(|b| b != binding);
+        }
+    }
+
+    pub fn unbind_axis(&mut self, action: &str, binding: &AxisBinding) {
+        if let Some(bindings) = self.axes.get_mut(action) {
+            bindings.retain(|b| b != binding);
+        }
+    }
+
+    pub fn clear_action(&mut self, action: &str) {
+        self.buttons.remove(action);
+        self.axes.remove(action);
+    }
+
+    pub fn clear(&mut self) {
+        self.buttons.clear();
+        self.axes.clear();
+    }
+
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.buttons.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn axis_bindings(&self, action: &str) -> &[AxisBinding] {
+        self.axes.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.buttons.keys().map(String::as_str)
+    }
+
+    pub fn axis_actions(&self) -> impl Iterator<Item = &str> {
+        self.axes.keys().map(String::as_str)
+    }
+
+    pub fn pressed(&self, action: &str, state: &InputState) -> bool {
+        self.bindings(action).iter().any(|binding| binding.pressed(state))
+    }
+
+    pub fn just_pressed(&self, action: &str, state: &InputState) -> bool {
+        let bindings = self.bindings(action);
+        bindings.iter().any(|binding| binding.just_pressed(state))
+            && !bindings.iter().any(|binding| binding.pressed(state) && !binding.just_pressed(state))
+    }
+
+    pub fn just_released(&self, action: &str, state: &InputState) -> bool {
+        let bindings = self.bindings(action);
+        bindings.iter().any(|binding| binding.just_released(state))
+            && !bindings.iter().any(|binding| binding.pressed(state))
+    }
+
+    pub fn axis(&self, action: &str, state: &InputState) -> f32 {
+        self.axis_bindings(action).iter().map(|binding| binding.value(state)).sum::<f32>().clamp(-1.0, 1.0)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        std::fs::read_to_string(path)?.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    // ---------------< PRIVATE >---------------
+
+    fn write_line<B: fmt::Display>(
+        f: &mut fmt::Formatter<'_>,
+        kind: &str,
+        action: &str,
+        bindings: &[B],
+    ) -> fmt::Result {
+        write!(f, "{kind} {action} =")?;
+        for (i, binding) in bindings.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {ALTERNATIVE_SEPARATOR}")?;
+            }
+            write!(f, " {binding}")?;
+        }
+        writeln!(f)
+    }
+}
+
+impl fmt::Display for InputMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buttons: Vec<_> = self.buttons.iter().collect();
+        buttons.sort_by_key(|(action, _)| *action);
+        for (action, bindings) in buttons {
+            Self::write_line(f, "button", action, bindings)?;
+        }
+
+        let mut axes: Vec<_> = self.axes.iter().collect();
+        axes.sort_by_key(|(action, _)| *action);
+        for (action, bindings) in axes {
+            Self::write_line(f, "axis", action, bindings)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for InputMap {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut map = Self::new();
+
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let Some((head, bindings)) = line.split_once('=') else {
+                return Err(ParseInputError::new("input map line", line));
+            };
+
+            let bindings = bindings.split(ALTERNATIVE_SEPARATOR).map(str::trim).filter(|b| !b.is_empty());
+
+            match head.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["button", action] => {
+                    let bindings = bindings.map(str::parse).collect::<Result<_, _>>()?;
+                    map.buttons.insert(action.to_string(), bindings);
+                }
+                ["axis", action] => {
+                    let bindings = bindings.map(str::parse).collect::<Result<_, _>>()?;
+                    map.axes.insert(action.to_string(), bindings);
+                }
+                _ => return Err(ParseInputError::new("input map line", line)),
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> InputMap {
+        InputMap::new()
+            .with_binding("jump", Key::Space)
+            .with_binding("jump", MouseButton::Left)
+            .with_binding("jump", GamepadButton::A)
+            .with_binding("save", Binding::chord([Key::LeftControl, Key::S]))
+            .with_binding("menu", Binding::new(GamepadButton::Start).with(Key::Escape).with(MouseButton::Right))
+            .with_button_axis("move_x", Key::A, Key::D)
+            .with_button_axis("move_x", Binding::chord([Key::LeftShift, Key::Left]), Key::Right)
+            .with_axis("move_x", GamepadAxis::LeftX)
+            .with_axis("throttle", GamepadAxis::RightTrigger)
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let map = sample();
+        let text = map.to_string();
+
+        assert_eq!(text.parse::<InputMap>(), Ok(map.clone()));
+        assert_eq!(text.parse::<InputMap>().unwrap().to_string(), text);
+        assert!(text.contains("button save = Key.LeftControl + Key.S\n"), "{text}");
+        assert!(
+            text.contains("axis move_x = Key.A, Key.D | Key.LeftShift + Key.Left, Key.Right | GamepadAxis.LeftX\n")
+        );
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let path = std::env::temp_dir().join(format!("paper_input_map_{}.txt", std::process::id()));
+        let map = sample();
+
+        map.save(&path).unwrap();
+        let loaded = InputMap::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), map);
+
+        let error = InputMap::load(std::env::temp_dir().join("paper_input_map_missing.txt")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn comments_blank_lines_and_spacing() {
+        let map: InputMap = "
+            # Movement
+            axis   move_x=Key.A,Key.D|GamepadAxis.LeftX
+
+            button jump =   Key.Space  |Mouse.Left
+            button idle =
+        "
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            map.axis_bindings("move_x"),
+            [AxisBinding::buttons(Key::A, Key::D), AxisBinding::Gamepad(GamepadAxis::LeftX)]
+        );
+        assert_eq!(map.bindings("jump"), [Binding::new(Key::Space), Binding::new(MouseButton::Left)]);
+        assert!(map.bindings("idle").is_empty());
+        assert!(map.actions().any(|action| action == "idle"));
+    }
+
+    #[test]
+    fn malformed_lines_are_rejected() {
+        for line in [
+            "button jump Key.Space",
+            "button = Key.Space",
+            "button two words = Key.Space",
+            "trigger jump = Key.Space",
+            "button jump = Key.Nope",
+            "button jump = Key.Space | Space",
+            "button jump = Key.Space +",
+            "button jump = Keyboard.Space",
+            "button jump = Gamepad.LeftX",
+            "axis move_x = Key.A",
+            "axis move_x = Key.A, Key.B, Key.C",
+            "axis move_x = GamepadAxis.Start",
+            "axis move_x = Key.A,",
+        ] {
+            let text = format!("button ok = Key.A\n{line}\n");
+            assert!(text.parse::<InputMap>().is_err(), "'{line}' should be rejected");
+        }
+
+        let error = "button jump = Key.Nope".parse::<InputMap>().unwrap_err();
+        assert_eq!(error.to_string(), "invalid key: 'Nope'");
+    }
+
+    #[test]
+    fn every_input_has_a_unique_name() {
+        let sources = Key::ALL
+            .into_iter()
+            .map(InputSource::Key)
+            .chain(MouseButton::ALL.into_iter().map(InputSource::MouseButton))
+            .chain(GamepadButton::ALL.into_iter().map(InputSource::GamepadButton));
+
+        let mut names = Vec::new();
+        for source in sources {
+            let name = source.to_string();
+            assert_eq!(name.parse::<InputSource>(), Ok(source), "'{name}' does not parse back");
+            names.push(name);
+        }
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+
+        for axis in GamepadAxis::ALL {
+            let binding = AxisBinding::Gamepad(axis);
+            assert_eq!(binding.to_string().parse::<AxisBinding>(), Ok(binding));
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{Action, Event};
+use std::{fmt, str::FromStr};
+
+use crate::{Action, Event, Modifiers, ParseInputError};
 
 #[rustfmt::skip]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -25,16 +27,61 @@ pub enum Key {
 }
 
 impl Key {
+    #[rustfmt::skip]
+    pub const ALL: [Key; 121] = [
+        Key::Space, Key::Apostrophe, Key::Comma, Key::Minus, Key::Period, Key::Slash,
+        Key::Num0, Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9,
+        Key::Semicolon, Key::Equal,
+        Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M, Key::N,
+        Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+        Key::LeftBracket, Key::RightBracket,
+        Key::Backslash,
+        Key::GraveAccent,
+        Key::World1, Key::World2,
+        Key::Escape, Key::Enter, Key::Tab, Key::Backspace, Key::Insert, Key::Delete,
+        Key::Right, Key::Left, Key::Down, Key::Up,
+        Key::PageUp, Key::PageDown, Key::Home, Key::End, Key::ScrollLock, Key::NumLock, Key::PrintScreen, Key::Pause,
+        Key::CapsLock,
+        Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+        Key::F13, Key::F14, Key::F15, Key::F16, Key::F17, Key::F18, Key::F19, Key::F20, Key::F21, Key::F22, Key::F23,
+        Key::F24, Key::F25,
+        Key::Kp0, Key::Kp1, Key::Kp2, Key::Kp3, Key::Kp4, Key::Kp5, Key::Kp6, Key::Kp7, Key::Kp8, Key::Kp9,
+        Key::KpDecimal, Key::KpDivide, Key::KpMultiply, Key::KpSubtract, Key::KpAdd, Key::KpEnter, Key::KpEqual,
+        Key::LeftShift, Key::LeftControl, Key::LeftAlt, Key::LeftSuper,
+        Key::RightShift, Key::RightControl, Key::RightAlt, Key::RightSuper,
+        Key::Menu,
+        Key::Unknown,
+    ];
+
     pub fn press(self) -> Event {
-        Event::Key(self, Action::Press)
+        Event::key(self, Action::Press)
     }
 
     pub fn release(self) -> Event {
-        Event::Key(self, Action::Release)
+        Event::key(self, Action::Release)
     }
 
     pub fn repeat(self) -> Event {
-        Event::Key(self, Action::Repeat)
+        Event::key(self, Action::Repeat)
+    }
+
+    /// A press that only matches while exactly `modifiers` are held, e.g. `Key::S.press_with(Modifiers::CONTROL)`.
+    pub fn press_with(self, modifiers: Modifiers) -> Event {
+        Event::key_with_modifiers(self, Action::Press, modifiers)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|key| key.to_string() == s).ok_or_else(|| ParseInputError::new("key", s))
     }
 }
 
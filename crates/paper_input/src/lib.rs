@@ -1,13 +1,24 @@
 mod action;
 mod event;
+mod gamepad;
+mod input_map;
 mod key;
+mod modifiers;
 mod mouse;
+mod state;
 
 pub use action::*;
 pub use event::*;
+pub use gamepad::*;
+pub use input_map::*;
 pub use key::*;
+pub use modifiers::*;
 pub use mouse::*;
+pub use state::*;
 
 pub mod prelude {
-    pub use crate::{Action, Event, Key, MouseButton};
+    pub use crate::{
+        Action, AxisBinding, Binding, Event, EventKind, Gamepad, GamepadAxis, GamepadButton, InputMap, InputSource,
+        InputState, Key, Modifiers, MouseButton,
+    };
 }
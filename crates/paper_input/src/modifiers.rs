@@ -0,0 +1,100 @@
+use std::{
+    fmt,
+    ops::{BitOr, BitOrAssign},
+    str::FromStr,
+};
+
+use crate::ParseInputError;
+
+const NAMES: [(&str, Modifiers); 4] = [
+    ("Shift", Modifiers::SHIFT),
+    ("Control", Modifiers::CONTROL),
+    ("Alt", Modifiers::ALT),
+    ("Super", Modifiers::SUPER),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Modifiers {
+    pub shift:     bool,
+    pub control:   bool,
+    pub alt:       bool,
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    pub const ALT: Self = Self { alt: true, ..Self::NONE };
+    pub const CONTROL: Self = Self { control: true, ..Self::NONE };
+    pub const NONE: Self = Self { shift: false, control: false, alt: false, super_key: false };
+    pub const SHIFT: Self = Self { shift: true, ..Self::NONE };
+    pub const SUPER: Self = Self { super_key: true, ..Self::NONE };
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    pub fn contains(&self, other: Modifiers) -> bool {
+        (self.shift || !other.shift)
+            && (self.control || !other.control)
+            && (self.alt || !other.alt)
+            && (self.super_key || !other.super_key)
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self {
+            shift:     self.shift || rhs.shift,
+            control:   self.control || rhs.control,
+            alt:       self.alt || rhs.alt,
+            super_key: self.super_key || rhs.super_key,
+        }
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+impl From<glfw::Modifiers> for Modifiers {
+    fn from(modifiers: glfw::Modifiers) -> Self {
+        Self {
+            shift:     modifiers.contains(glfw::Modifiers::Shift),
+            control:   modifiers.contains(glfw::Modifiers::Control),
+            alt:       modifiers.contains(glfw::Modifiers::Alt),
+            super_key: modifiers.contains(glfw::Modifiers::Super),
+        }
+    }
+}
+
+/// Written as the held modifiers joined by `+`, e.g. `Shift+Control`, or `None`.
+impl fmt::Display for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "None");
+        }
+        let names: Vec<&str> =
+            NAMES.iter().filter(|(_, modifier)| self.contains(*modifier)).map(|(name, _)| *name).collect();
+        write!(f, "{}", names.join("+"))
+    }
+}
+
+impl FromStr for Modifiers {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "None" {
+            return Ok(Self::NONE);
+        }
+        s.split('+').try_fold(Self::NONE, |modifiers, name| {
+            NAMES
+                .iter()
+                .find(|(n, _)| *n == name.trim())
+                .map(|(_, modifier)| modifiers | *modifier)
+                .ok_or_else(|| ParseInputError::new("modifiers", s))
+        })
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{Action, Event};
+use std::{fmt, str::FromStr};
+
+use crate::{Action, Event, ParseInputError};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MouseButton {
@@ -13,6 +15,17 @@ pub enum MouseButton {
 }
 
 impl MouseButton {
+    pub const ALL: [MouseButton; 8] = [
+        MouseButton::Left,
+        MouseButton::Right,
+        MouseButton::Middle,
+        MouseButton::Button4,
+        MouseButton::Button5,
+        MouseButton::Button6,
+        MouseButton::Button7,
+        MouseButton::Button8,
+    ];
+
     pub fn press(self) -> Event {
         Event::MouseButton(self, Action::Press)
     }
@@ -26,6 +39,23 @@ impl MouseButton {
     }
 }
 
+impl fmt::Display for MouseButton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl FromStr for MouseButton {
+    type Err = ParseInputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|button| button.to_string() == s)
+            .ok_or_else(|| ParseInputError::new("mouse button", s))
+    }
+}
+
 impl From<glfw::MouseButton> for MouseButton {
     fn from(button: glfw::MouseButton) -> Self {
         match button {
@@ -0,0 +1,376 @@
+use std::hash::Hash;
+
+use hashbrown::{HashMap, HashSet};
+use paper_math::Vec2;
+
+use crate::{Action, Event, Gamepad, GamepadAxis, GamepadButton, GamepadSnapshot, GamepadState, Key, MouseButton};
+
+const DEFAULT_GAMEPAD_DEADZONE: f32 = 0.1;
+
+#[derive(Debug, Clone)]
+pub struct ButtonInput<T: Copy + Eq + Hash> {
+    pressed:       HashSet<T>,
+    just_pressed:  HashSet<T>,
+    just_released: HashSet<T>,
+}
+
+impl<T: Copy + Eq + Hash> ButtonInput<T> {
+    pub fn press(&mut self, input: T) {
+        if self.pressed.insert(input) {
+            self.just_pressed.insert(input);
+        }
+    }
+
+    pub fn release(&mut self, input: T) {
+        if self.pressed.remove(&input) {
+            self.just_released.insert(input);
+        }
+    }
+
+    pub fn release_all(&mut self) {
+        self.just_released.extend(self.pressed.drain());
+    }
+
+    pub fn pressed(&self, input: T) -> bool {
+        self.pressed.contains(&input)
+    }
+
+    pub fn just_pressed(&self, input: T) -> bool {
+        self.just_pressed.contains(&input)
+    }
+
+    pub fn just_released(&self, input: T) -> bool {
+        self.just_released.contains(&input)
+    }
+
+    pub fn any_pressed(&self, inputs: impl IntoIterator<Item = T>) -> bool {
+        inputs.into_iter().any(|input| self.pressed(input))
+    }
+
+    pub fn all_pressed(&self, inputs: impl IntoIterator<Item = T>) -> bool {
+        inputs.into_iter().all(|input| self.pressed(input))
+    }
+
+    pub fn get_pressed(&self) -> impl Iterator<Item = &T> {
+        self.pressed.iter()
+    }
+
+    pub fn get_just_pressed(&self) -> impl Iterator<Item = &T> {
+        self.just_pressed.iter()
+    }
+
+    pub fn get_just_released(&self) -> impl Iterator<Item = &T> {
+        self.just_released.iter()
+    }
+
+    pub fn clear_just(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+
+    pub(crate) fn apply(&mut self, input: T, action: Action) {
+        match action {
+            Action::Press => self.press(input),
+            Action::Release => self.release(input),
+            Action::Repeat => {}
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash> Default for ButtonInput<T> {
+    fn default() -> Self {
+        Self { pressed: HashSet::new(), just_pressed: HashSet::new(), just_released: HashSet::new() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InputState {
+    keys:          ButtonInput<Key>,
+    mouse_buttons: ButtonInput<MouseButton>,
+
+    mouse_position: Vec2,
+    mouse_delta:    Vec2,
+    mouse_scroll:   Vec2,
+
+    gamepads:         HashMap<Gamepad, GamepadState>,
+    gamepad_deadzone: f32,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn keys(&self) -> &ButtonInput<Key> {
+        &self.keys
+    }
+
+    pub fn mouse_buttons(&self) -> &ButtonInput<MouseButton> {
+        &self.mouse_buttons
+    }
+
+    pub fn key_pressed(&self, key: Key) -> bool {
+        self.keys.pressed(key)
+    }
+
+    pub fn key_just_pressed(&self, key: Key) -> bool {
+        self.keys.just_pressed(key)
+    }
+
+    pub fn key_just_released(&self, key: Key) -> bool {
+        self.keys.just_released(key)
+    }
+
+    pub fn mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.pressed(button)
+    }
+
+    pub fn mouse_button_just_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.just_pressed(button)
+    }
+
+    pub fn mouse_button_just_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.just_released(button)
+    }
+
+    pub fn mouse_position(&self) -> Vec2 {
+        self.mouse_position
+    }
+
+    /// Mouse movement accumulated since the last call to [`InputState::end_frame`].
+    pub fn mouse_delta(&self) -> Vec2 {
+        self.mouse_delta
+    }
+
+    pub fn gamepads(&self) -> impl Iterator<Item = Gamepad> + '_ {
+        self.gamepads.keys().copied()
+    }
+
+    pub fn gamepad(&self, gamepad: Gamepad) -> Option<&GamepadState> {
+        self.gamepads.get(&gamepad)
+    }
+
+    pub fn gamepad_button_pressed(&self, gamepad: Gamepad, button: GamepadButton) -> bool {
+        self.gamepad(gamepad).is_some_and(|state| state.buttons().pressed(button))
+    }
+
+    pub fn gamepad_button_just_pressed(&self, gamepad: Gamepad, button: GamepadButton) -> bool {
+        self.gamepad(gamepad).is_some_and(|state| state.buttons().just_pressed(button))
+    }
+
+    pub fn gamepad_button_just_released(&self, gamepad: Gamepad, button: GamepadButton) -> bool {
+        self.gamepad(gamepad).is_some_and(|state| state.buttons().just_released(button))
+    }
+
+    pub fn gamepad_axis(&self, gamepad: Gamepad, axis: GamepadAxis) -> f32 {
+        self.gamepad(gamepad).map_or(0.0, |state| state.axis(axis))
+    }
+
+    pub fn any_gamepad_button_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepads.values().any(|state| state.buttons().pressed(button))
+    }
+
+    pub fn any_gamepad_button_just_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepads.values().any(|state| state.buttons().just_pressed(button))
+    }
+
+    pub fn any_gamepad_button_just_released(&self, button: GamepadButton) -> bool {
+        self.gamepads.values().any(|state| state.buttons().just_released(button))
+    }
+
+    /// The value of `axis` with the largest magnitude across all connected gamepads.
+    pub fn any_gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+        self.gamepads.values().map(|state| state.axis(axis)).fold(0.0, |a, b| if b.abs() > a.abs() { b } else { a })
+    }
+
+    pub fn gamepad_deadzone(&self) -> f32 {
+        self.gamepad_deadzone
+    }
+
+    pub fn set_gamepad_deadzone(&mut self, deadzone: f32) {
+        self.gamepad_deadzone = deadzone.clamp(0.0, 0.99);
+    }
+
+    /// Applies the latest snapshots of every connected gamepad and returns the resulting
+    /// connect, disconnect and button events.
+    pub fn update_gamepads(&mut self, snapshots: Vec<(Gamepad, GamepadSnapshot)>) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        let disconnected: Vec<Gamepad> =
+            self.gamepads.keys().filter(|gamepad| !snapshots.iter().any(|(g, _)| g == *gamepad)).copied().collect();
+        for gamepad in disconnected {
+            self.gamepads.remove(&gamepad);
+            events.push(Event::GamepadDisconnected(gamepad));
+        }
+
+        for (gamepad, snapshot) in snapshots {
+            let state = self.gamepads.entry(gamepad).or_insert_with(|| {
+                events.push(Event::GamepadConnected(gamepad));
+                GamepadState::default()
+            });
+            events.extend(state.update(gamepad, snapshot, self.gamepad_deadzone));
+        }
+
+        events
+    }
+
+    /// Scroll offset accumulated since the last call to [`InputState::end_frame`], positive `y` is up.
+    pub fn mouse_scroll(&self) -> Vec2 {
+        self.mouse_scroll
+    }
+
+    /// Moves the cursor without contributing to [`InputState::mouse_delta`].
+    pub fn set_mouse_position(&mut self, position: Vec2) {
+        self.mouse_position = position;
+    }
+
+    pub fn process(&mut self, event: &Event) {
+        match *event {
+            Event::Key { key, action, .. } => self.keys.apply(key, action),
+            Event::MouseButton(button, action) => self.mouse_buttons.apply(button, action),
+            Event::MouseMove(x, y) => {
+                let position = Vec2::new(x as f32, y as f32);
+                self.mouse_delta += position - self.mouse_position;
+                self.mouse_position = position;
+            }
+            Event::MouseScroll(x, y) => {
+                self.mouse_scroll += Vec2::new(x as f32, y as f32);
+            }
+            Event::GamepadConnected(gamepad) => {
+                self.gamepads.entry(gamepad).or_default();
+            }
+            Event::GamepadDisconnected(gamepad) => {
+                self.gamepads.remove(&gamepad);
+            }
+            Event::GamepadButton(gamepad, button, action) => {
+                self.gamepads.entry(gamepad).or_default().buttons_mut().apply(button, action);
+            }
+            Event::Focus(false) => {
+                self.keys.release_all();
+                self.mouse_buttons.release_all();
+            }
+            _ => {}
+        }
+    }
+
+    pub fn end_frame(&mut self) {
+        self.keys.clear_just();
+        self.mouse_buttons.clear_just();
+        self.gamepads.values_mut().for_each(|state| state.buttons_mut().clear_just());
+        self.mouse_delta = Vec2::ZERO;
+        self.mouse_scroll = Vec2::ZERO;
+    }
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self {
+            keys: ButtonInput::default(),
+            mouse_buttons: ButtonInput::default(),
+            mouse_position: Vec2::ZERO,
+            mouse_delta: Vec2::ZERO,
+            mouse_scroll: Vec2::ZERO,
+            gamepads: HashMap::new(),
+            gamepad_deadzone: DEFAULT_GAMEPAD_DEADZONE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Modifiers;
+
+    fn key(key: Key, action: Action) -> Event {
+        Event::Key { key, action, modifiers: Modifiers::NONE, scancode: 0 }
+    }
+
+    #[test]
+    fn just_pressed_lasts_one_frame() {
+        let mut input = InputState::new();
+        input.process(&key(Key::W, Action::Press));
+
+        assert!(input.key_pressed(Key::W));
+        assert!(input.key_just_pressed(Key::W));
+
+        input.process(&key(Key::W, Action::Repeat));
+        input.end_frame();
+        assert!(input.key_pressed(Key::W));
+        assert!(!input.key_just_pressed(Key::W));
+
+        // Holding a key does not press it again
+        input.process(&key(Key::W, Action::Press));
+        assert!(!input.key_just_pressed(Key::W));
+    }
+
+    #[test]
+    fn just_released_lasts_one_frame() {
+        let mut input = InputState::new();
+        input.process(&Event::MouseButton(MouseButton::Left, Action::Press));
+        input.end_frame();
+
+        input.process(&Event::MouseButton(MouseButton::Left, Action::Release));
+        assert!(!input.mouse_button_pressed(MouseButton::Left));
+        assert!(input.mouse_button_just_released(MouseButton::Left));
+
+        input.end_frame();
+        assert!(!input.mouse_button_just_released(MouseButton::Left));
+
+        // Releasing a button that is not held does nothing
+        input.process(&Event::MouseButton(MouseButton::Right, Action::Release));
+        assert!(!input.mouse_button_just_released(MouseButton::Right));
+    }
+
+    #[test]
+    fn press_and_release_in_the_same_frame() {
+        let mut input = InputState::new();
+        input.process(&key(Key::Space, Action::Press));
+        input.process(&key(Key::Space, Action::Release));
+
+        assert!(!input.key_pressed(Key::Space));
+        assert!(input.key_just_pressed(Key::Space));
+        assert!(input.key_just_released(Key::Space));
+
+        input.end_frame();
+        assert!(!input.key_just_pressed(Key::Space));
+        assert!(!input.key_just_released(Key::Space));
+    }
+
+    #[test]
+    fn losing_focus_releases_everything() {
+        let mut input = InputState::new();
+        input.process(&key(Key::A, Action::Press));
+        input.process(&key(Key::LeftShift, Action::Press));
+        input.process(&Event::MouseButton(MouseButton::Left, Action::Press));
+        input.end_frame();
+
+        input.process(&Event::Focus(true));
+        assert!(input.key_pressed(Key::A));
+
+        input.process(&Event::Focus(false));
+        assert_eq!(input.keys().get_pressed().count(), 0);
+        assert_eq!(input.mouse_buttons().get_pressed().count(), 0);
+        assert!(input.key_just_released(Key::A) && input.key_just_released(Key::LeftShift));
+        assert!(input.mouse_button_just_released(MouseButton::Left));
+    }
+
+    #[test]
+    fn mouse_delta_and_scroll_reset_each_frame() {
+        let mut input = InputState::new();
+        input.set_mouse_position(Vec2::new(10.0, 10.0));
+        assert_eq!(input.mouse_delta(), Vec2::ZERO);
+
+        input.process(&Event::MouseMove(12.0, 9.0));
+        input.process(&Event::MouseMove(15.0, 11.0));
+        input.process(&Event::MouseScroll(0.0, 1.0));
+        input.process(&Event::MouseScroll(0.5, -3.0));
+        assert_eq!(input.mouse_position(), Vec2::new(15.0, 11.0));
+        assert_eq!(input.mouse_delta(), Vec2::new(5.0, 1.0));
+        assert_eq!(input.mouse_scroll(), Vec2::new(0.5, -2.0));
+
+        input.end_frame();
+        assert_eq!(input.mouse_position(), Vec2::new(15.0, 11.0));
+        assert_eq!(input.mouse_delta(), Vec2::ZERO);
+        assert_eq!(input.mouse_scroll(), Vec2::ZERO);
+    }
+}
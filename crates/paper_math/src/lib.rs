@@ -1,11 +1,13 @@
+mod rect;
 mod transform;
 
+pub use rect::*;
 pub use transform::*;
 
 pub mod prelude {
     pub use crate::{
         BVec2, BVec3, BVec4, BVec4A, IVec2, IVec3, IVec4, Mat2, Mat3, Mat3A, Mat4, Quat, UVec2, UVec3, UVec4, Vec2,
-        Vec2Swizzles, Vec3, Vec3A, Vec3Swizzles, Vec4, Vec4Swizzles, transform::Transform,
+        Vec2Swizzles, Vec3, Vec3A, Vec3Swizzles, Vec4, Vec4Swizzles, rect::Rect, transform::Transform,
     };
 }
 
@@ -0,0 +1,63 @@
+use glam::Vec2;
+
+/// An axis aligned rectangle given by its minimum and maximum corner.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Rect {
+    pub const ZERO: Self = Self { min: Vec2::ZERO, max: Vec2::ZERO };
+
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min: min.min(max), max: min.max(max) }
+    }
+
+    pub fn from_center_size(center: Vec2, size: Vec2) -> Self {
+        let half = size.abs() * 0.5;
+        Self { min: center - half, max: center + half }
+    }
+
+    /// The smallest rectangle containing all points, [`Rect::ZERO`] if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Vec2>) -> Self {
+        let mut points = points.into_iter();
+        let Some(first) = points.next() else {
+            return Self::ZERO;
+        };
+
+        points.fold(Self { min: first, max: first }, |rect, point| rect.include(point))
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    pub fn include(self, point: Vec2) -> Self {
+        Self { min: self.min.min(point), max: self.max.max(point) }
+    }
+
+    pub fn union(self, other: Rect) -> Self {
+        Self { min: self.min.min(other.min), max: self.max.max(other.max) }
+    }
+
+    pub fn translate(self, offset: Vec2) -> Self {
+        Self { min: self.min + offset, max: self.max + offset }
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static VERTEX_ARRAYS: AtomicUsize = AtomicUsize::new(0);
+static BUFFERS: AtomicUsize = AtomicUsize::new(0);
+static PROGRAMS: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of GPU objects created by paper that have not been deleted yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct GpuObjects {
+    pub vertex_arrays: usize,
+    pub buffers:       usize,
+    pub programs:      usize,
+}
+
+impl GpuObjects {
+    pub fn live() -> Self {
+        Self {
+            vertex_arrays: VERTEX_ARRAYS.load(Ordering::Relaxed),
+            buffers:       BUFFERS.load(Ordering::Relaxed),
+            programs:      PROGRAMS.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.vertex_arrays + self.buffers + self.programs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+}
+
+impl fmt::Display for GpuObjects {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} vertex arrays, {} buffers, {} programs", self.vertex_arrays, self.buffers, self.programs)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GpuObject {
+    #[cfg(feature = "internal")]
+    VertexArray,
+    #[cfg(feature = "internal")]
+    Buffer,
+    Program,
+}
+
+impl GpuObject {
+    pub(crate) fn created(self) {
+        self.counter().fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn deleted(self) {
+        self.counter().fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn counter(self) -> &'static AtomicUsize {
+        match self {
+            #[cfg(feature = "internal")]
+            GpuObject::VertexArray => &VERTEX_ARRAYS,
+            #[cfg(feature = "internal")]
+            GpuObject::Buffer => &BUFFERS,
+            GpuObject::Program => &PROGRAMS,
+        }
+    }
+}
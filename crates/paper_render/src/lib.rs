@@ -1,12 +1,20 @@
+mod gpu;
 mod material;
 mod mesh;
+mod path;
+mod svg;
 
+pub use gpu::*;
 pub use material::*;
 pub use mesh::*;
+pub use path::*;
+pub use svg::*;
 
 pub mod prelude {
     pub use crate::{
         material::{DefaultMaterial, Material},
-        mesh::{AttributeType, Mesh, Shape2D},
+        mesh::{AttributeType, LineCap, LineJoin, Mesh, Shape2D, Stroke, Topology, VertexFormat},
+        path::{FillRule, Path},
+        svg::Svg,
     };
 }
@@ -2,7 +2,7 @@ use hashbrown::HashMap;
 
 use crate::{Material, Shader, ShaderUniform, Uniform};
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, PartialEq, Default)]
 pub struct ColorMaterial {
     shader:   Shader,
     uniforms: HashMap<String, Uniform>,
@@ -1,6 +1,8 @@
 use glad_gl::gl;
 use log::{debug, error};
 
+use crate::gpu::GpuObject;
+
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub enum ShaderUniform {
     Float(f32),
@@ -31,9 +33,11 @@ pub enum ShaderUniform {
     Texture(u32),
 }
 
-const DUMMY_SHADER: Shader = Shader { id: u32::MAX };
+const DUMMY_SHADER_ID: u32 = u32::MAX;
+const DUMMY_SHADER: Shader = Shader { id: DUMMY_SHADER_ID };
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Owns a linked shader program, which is deleted when the shader is dropped.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Shader {
     id: u32,
 }
@@ -72,6 +76,7 @@ impl Shader {
 
         debug!("Linking program");
         let id = Self::link_program(vertex_shader, fragment_shader);
+        GpuObject::Program.created();
 
         Self { id }
     }
@@ -178,8 +183,12 @@ impl Default for Shader {
 
 impl Drop for Shader {
     fn drop(&mut self) {
+        if self.id == DUMMY_SHADER_ID {
+            return;
+        }
         unsafe {
             gl::DeleteProgram(self.id);
         }
+        GpuObject::Program.deleted();
     }
 }
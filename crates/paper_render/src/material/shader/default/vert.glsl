@@ -10,5 +10,5 @@ out vec4 fColor;
 
 void main() {
     gl_Position = uProjection * uModel * vec4(aPos, 1.0);
-    fColor = vec4(aColor.rgb, 1.0);
+    fColor = aColor;
 }
@@ -1,18 +1,31 @@
+use crate::VertexFormat;
+
 pub(crate) const POSITION_SIZE: u32 = 3;
 pub(crate) const COLOR_SIZE: u32 = 4;
 
 pub(crate) const DEFAULT_POSITION: [f32; POSITION_SIZE as usize] = [0.0; POSITION_SIZE as usize];
 pub(crate) const DEFAULT_COLOR: [f32; COLOR_SIZE as usize] = [0.0; COLOR_SIZE as usize];
 
+/// The kind of a vertex attribute, which also decides its shader location.
+///
+/// Locations are fixed: positions are always at `layout(location = 0)` and colors at `1`, whether or
+/// not a mesh has them. Custom attributes take the locations from `2` upwards in the order they were
+/// added to the [`Mesh`](crate::Mesh).
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum AttributeType {
     Position,
     Color,
 
+    /// Starts at location `2` even if the mesh has no colors.
     Custom { name: String, size: u32 },
 }
 
 impl AttributeType {
+    /// Two component texture coordinates, as generated for [`Shape2D`](crate::Shape2D) meshes.
+    pub fn uv() -> Self {
+        AttributeType::Custom { name: crate::UV_ATTRIBUTE.to_string(), size: 2 }
+    }
+
     pub fn name(&self) -> &str {
         match self {
             AttributeType::Position => "position",
@@ -34,8 +47,9 @@ impl AttributeType {
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct VertexAttribute {
-    pub name: String,
-    pub size: u32,
+    pub name:   String,
+    pub size:   u32,
+    pub format: VertexFormat,
 
     __type: AttributeType,
 }
@@ -45,7 +59,17 @@ impl VertexAttribute {
         Self {
             name:   attribute_type.name().to_string(),
             size:   attribute_type.size(),
+            format: VertexFormat::default(),
             __type: attribute_type.clone(),
         }
     }
+
+    pub fn attribute_type(&self) -> &AttributeType {
+        &self.__type
+    }
+
+    /// Size in bytes of one value of this attribute in a vertex buffer.
+    pub fn byte_size(&self) -> u32 {
+        self.size * self.format.component_size()
+    }
 }
@@ -0,0 +1,181 @@
+/// How the components of a vertex attribute are stored in the vertex buffer.
+///
+/// Attribute data is always given as `f32` and converted when the mesh is uploaded. Integer formats are
+/// passed to shaders as integers (`int`/`uint` inputs), so values above `2^24` lose precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum VertexFormat {
+    #[default]
+    F32,
+    /// Half precision float, read as `float` in shaders.
+    F16,
+    /// Unsigned byte, read as `uint` in shaders.
+    U8,
+    /// Unsigned byte mapping `0.0..=1.0` to `0..=255`, read as `float` in shaders. Suited to colors.
+    U8Norm,
+    /// Unsigned short, read as `uint` in shaders.
+    U16,
+    /// Signed int, read as `int` in shaders.
+    I32,
+}
+
+impl VertexFormat {
+    /// Size of a single component in bytes.
+    pub const fn component_size(&self) -> u32 {
+        match self {
+            VertexFormat::F32 | VertexFormat::I32 => 4,
+            VertexFormat::F16 | VertexFormat::U16 => 2,
+            VertexFormat::U8 | VertexFormat::U8Norm => 1,
+        }
+    }
+
+    /// Whether shaders receive the values as integers instead of floats.
+    pub const fn is_integer(&self) -> bool {
+        matches!(self, VertexFormat::U8 | VertexFormat::U16 | VertexFormat::I32)
+    }
+
+    #[cfg(feature = "internal")]
+    pub fn gl_type(&self) -> glad_gl::gl::types::GLenum {
+        use glad_gl::gl;
+
+        match self {
+            VertexFormat::F32 => gl::FLOAT,
+            VertexFormat::F16 => gl::HALF_FLOAT,
+            VertexFormat::U8 | VertexFormat::U8Norm => gl::UNSIGNED_BYTE,
+            VertexFormat::U16 => gl::UNSIGNED_SHORT,
+            VertexFormat::I32 => gl::INT,
+        }
+    }
+
+    #[cfg(feature = "internal")]
+    pub fn is_normalized(&self) -> bool {
+        matches!(self, VertexFormat::U8Norm)
+    }
+
+    /// Appends `values` converted to this format to `bytes`, in native byte order.
+    #[cfg(feature = "internal")]
+    pub(crate) fn write(&self, values: &[f32], bytes: &mut Vec<u8>) {
+        for &value in values {
+            match self {
+                VertexFormat::F32 => bytes.extend(value.to_ne_bytes()),
+                VertexFormat::F16 => bytes.extend(f16_bits(value).to_ne_bytes()),
+                VertexFormat::U8 => bytes.push(value as u8),
+                VertexFormat::U8Norm => bytes.push((value.clamp(0.0, 1.0) * 255.0).round() as u8),
+                VertexFormat::U16 => bytes.extend((value as u16).to_ne_bytes()),
+                VertexFormat::I32 => bytes.extend((value as i32).to_ne_bytes()),
+            }
+        }
+    }
+}
+
+// ---------------< PRIVATE >---------------
+
+/// IEEE 754 half precision bits of `value`, rounding to nearest with ties to even.
+#[cfg(feature = "internal")]
+fn f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    if exponent <= 0 {
+        // Too small for a normal half, encode as subnormal or flush to zero
+        if exponent < -10 {
+            return sign;
+        }
+        let half = round_shifted(mantissa | 0x80_0000, (14 - exponent) as u32);
+        return sign | half as u16;
+    }
+
+    // A carry out of the mantissa correctly bumps the exponent, up to infinity
+    let half = round_shifted((exponent as u32) << 23 | mantissa, 13);
+    sign | half as u16
+}
+
+/// `value >> shift` rounded to nearest with ties to even.
+#[cfg(feature = "internal")]
+fn round_shifted(value: u32, shift: u32) -> u32 {
+    let shifted = value >> shift;
+    let remainder = value & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+
+    if remainder > halfway || (remainder == halfway && shifted & 1 == 1) { shifted + 1 } else { shifted }
+}
+
+#[cfg(all(test, feature = "internal"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f16_exact_values() {
+        assert_eq!(f16_bits(0.0), 0x0000);
+        assert_eq!(f16_bits(-0.0), 0x8000);
+        assert_eq!(f16_bits(1.0), 0x3c00);
+        assert_eq!(f16_bits(-2.5), 0xc100);
+        assert_eq!(f16_bits(65504.0), 0x7bff);
+        // Smallest normal and the largest and smallest subnormals
+        assert_eq!(f16_bits(2f32.powi(-14)), 0x0400);
+        assert_eq!(f16_bits(2f32.powi(-14) - 2f32.powi(-24)), 0x03ff);
+        assert_eq!(f16_bits(2f32.powi(-24)), 0x0001);
+    }
+
+    #[test]
+    fn f16_overflow_and_nan() {
+        assert_eq!(f16_bits(65520.0), 0x7c00);
+        assert_eq!(f16_bits(1e6), 0x7c00);
+        assert_eq!(f16_bits(-1e6), 0xfc00);
+        assert_eq!(f16_bits(f32::INFINITY), 0x7c00);
+        assert_eq!(f16_bits(f32::NEG_INFINITY), 0xfc00);
+
+        let nan = f16_bits(f32::NAN);
+        assert_eq!(nan & 0x7c00, 0x7c00);
+        assert_ne!(nan & 0x03ff, 0);
+    }
+
+    #[test]
+    fn f16_flushes_to_zero() {
+        assert_eq!(f16_bits(2f32.powi(-26)), 0x0000);
+        assert_eq!(f16_bits(-2f32.powi(-30)), 0x8000);
+        assert_eq!(f16_bits(f32::MIN_POSITIVE), 0x0000);
+        // Half of the smallest subnormal is a tie, rounded to the even zero
+        assert_eq!(f16_bits(2f32.powi(-25)), 0x0000);
+        assert_eq!(f16_bits(1.5 * 2f32.powi(-25)), 0x0001);
+    }
+
+    #[test]
+    fn f16_rounds_ties_to_even() {
+        let ulp = 2f32.powi(-10);
+        assert_eq!(f16_bits(1.0 + ulp * 0.5), 0x3c00);
+        assert_eq!(f16_bits(1.0 + ulp * 1.5), 0x3c02);
+        assert_eq!(f16_bits(1.0 + ulp * 0.5001), 0x3c01);
+        assert_eq!(f16_bits(1.0 + ulp * 0.4999), 0x3c00);
+        // Subnormal ties
+        assert_eq!(f16_bits(2.5 * 2f32.powi(-24)), 0x0002);
+        assert_eq!(f16_bits(3.5 * 2f32.powi(-24)), 0x0004);
+    }
+
+    #[test]
+    fn write_converts_each_format() {
+        let write = |format: VertexFormat, values: &[f32]| {
+            let mut bytes = Vec::new();
+            format.write(values, &mut bytes);
+            bytes
+        };
+
+        assert_eq!(write(VertexFormat::F32, &[1.5]), 1.5f32.to_ne_bytes());
+        assert_eq!(write(VertexFormat::F16, &[1.0]), 0x3c00u16.to_ne_bytes());
+        assert_eq!(write(VertexFormat::U8, &[7.0, 300.0, -1.0]), [7, 255, 0]);
+        assert_eq!(write(VertexFormat::U8Norm, &[0.0, 0.5, 1.0, 2.0]), [0, 128, 255, 255]);
+        assert_eq!(write(VertexFormat::U16, &[513.0]), 513u16.to_ne_bytes());
+        assert_eq!(write(VertexFormat::I32, &[-7.0]), (-7i32).to_ne_bytes());
+    }
+}
@@ -1,20 +1,25 @@
 use std::hash::Hash;
 
+#[cfg(feature = "internal")]
 use glad_gl::gl;
+use log::error;
 
-use crate::{AttributeType, COLOR_SIZE, POSITION_SIZE, Vertex, VertexAttribute};
-
-pub(crate) const F32_SIZE: u32 = size_of::<f32>() as u32;
-pub(crate) const U32_SIZE: u32 = size_of::<u32>() as u32;
+#[cfg(feature = "internal")]
+use crate::gpu::GpuObject;
+use crate::{AttributeType, Topology, Vertex, VertexAttribute, VertexFormat};
 
-pub(crate) const BASE_STRIDE: u32 = POSITION_SIZE * F32_SIZE + COLOR_SIZE * F32_SIZE;
+/// Alignment in bytes of each attribute within an interleaved vertex.
+#[cfg(feature = "internal")]
+const ATTRIBUTE_ALIGNMENT: u32 = 4;
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Default)]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Mesh {
     vertices:   Vec<Vertex>,
     attributes: Vec<VertexAttribute>,
     indices:    Option<Vec<u32>>,
-    stride:     u32,
+    topology:   Topology,
+    point_size: f32,
+    line_width: f32,
 }
 
 impl Mesh {
@@ -22,9 +27,51 @@ impl Mesh {
         Self::default()
     }
 
-    pub fn add_attribute(&mut self, attribute_type: AttributeType, data: Vec<f32>) {
-        self.stride += attribute_type.size() * F32_SIZE;
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.set_topology(topology);
+        self
+    }
+
+    /// Size of rendered points in pixels, used with [`Topology::Points`].
+    pub fn point_size(&self) -> f32 {
+        self.point_size
+    }
 
+    pub fn set_point_size(&mut self, point_size: f32) {
+        self.point_size = point_size;
+    }
+
+    pub fn with_point_size(mut self, point_size: f32) -> Self {
+        self.set_point_size(point_size);
+        self
+    }
+
+    /// Width of rendered lines in pixels, used with the line topologies. Core profile contexts are
+    /// only required to support a width of `1.0`, wider lines are clamped by the driver.
+    pub fn line_width(&self) -> f32 {
+        self.line_width
+    }
+
+    pub fn set_line_width(&mut self, line_width: f32) {
+        self.line_width = line_width;
+    }
+
+    pub fn with_line_width(mut self, line_width: f32) -> Self {
+        self.set_line_width(line_width);
+        self
+    }
+
+    /// Adds per vertex data, `data` holds [`AttributeType::size`] values per vertex. The shader
+    /// location depends on the attribute type, see [`AttributeType`].
+    pub fn add_attribute(&mut self, attribute_type: AttributeType, data: Vec<f32>) {
         self.attributes.push(VertexAttribute::new(&attribute_type));
 
         if self.vertices.is_empty() {
@@ -48,6 +95,29 @@ impl Mesh {
         self
     }
 
+    /// Sets how the data of an added attribute is stored on the GPU, [`VertexFormat::F32`] by default.
+    /// Positions and colors must use a float format.
+    pub fn set_attribute_format(&mut self, attribute_type: &AttributeType, format: VertexFormat) {
+        if format.is_integer() && !matches!(attribute_type, AttributeType::Custom { .. }) {
+            error!("Attribute '{}' can not use the integer format {:?}", attribute_type.name(), format);
+            return;
+        }
+
+        match self.attributes.iter_mut().find(|attr| attr.attribute_type() == attribute_type) {
+            Some(attr) => attr.format = format,
+            None => error!("Can not set the format of missing attribute '{}'", attribute_type.name()),
+        }
+    }
+
+    pub fn with_attribute_format(mut self, attribute_type: &AttributeType, format: VertexFormat) -> Self {
+        self.set_attribute_format(attribute_type, format);
+        self
+    }
+
+    pub fn attributes(&self) -> &[VertexAttribute] {
+        &self.attributes
+    }
+
     pub fn set_indices(&mut self, indices: Vec<u32>) {
         self.indices = Some(indices);
     }
@@ -56,10 +126,60 @@ impl Mesh {
         self.set_indices(indices);
         self
     }
+
+    // ---------------< PRIVATE >---------------
+
+    /// Size in bytes of an interleaved vertex.
+    #[cfg(feature = "internal")]
+    fn vertex_stride(&self) -> u32 {
+        self.attributes.iter().map(|attr| attr.byte_size().next_multiple_of(ATTRIBUTE_ALIGNMENT)).sum()
+    }
+
+    /// Shader location and byte offset within an interleaved vertex of each attribute, in the order they
+    /// were added. Positions are always at location 0 and colors at 1, custom attributes follow.
+    #[cfg(feature = "internal")]
+    fn attribute_layout(&self) -> Vec<(u32, u32)> {
+        let mut custom_location = 2;
+        let mut offset = 0;
+
+        self.attributes
+            .iter()
+            .map(|attr| {
+                let location = match attr.attribute_type() {
+                    AttributeType::Position => 0,
+                    AttributeType::Color => 1,
+                    AttributeType::Custom { .. } => {
+                        custom_location += 1;
+                        custom_location - 1
+                    }
+                };
+                let layout = (location, offset);
+                offset += attr.byte_size().next_multiple_of(ATTRIBUTE_ALIGNMENT);
+                layout
+            })
+            .collect()
+    }
+
+    /// The vertices packed in the layout given by [`Mesh::attribute_layout`].
+    #[cfg(feature = "internal")]
+    fn vertex_data(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity((self.vertex_stride() as usize) * self.vertices.len());
+
+        for vertex in &self.vertices {
+            for attr in &self.attributes {
+                let start = bytes.len();
+                attr.format.write(vertex.attribute(attr.attribute_type()).unwrap_or_default(), &mut bytes);
+                bytes.resize(start + attr.byte_size().next_multiple_of(ATTRIBUTE_ALIGNMENT) as usize, 0);
+            }
+        }
+
+        bytes
+    }
 }
 
+/// A [`Mesh`] uploaded to the GPU, the GPU objects are deleted when it is dropped.
 #[cfg(feature = "internal")]
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, PartialEq, PartialOrd)]
 pub struct InternalMesh {
     pub mesh: Mesh,
 
@@ -67,7 +187,10 @@ pub struct InternalMesh {
     vbo: u32,
     ebo: Option<u32>,
 
-    draw_mode: gl::types::GLenum,
+    /// Allocated sizes of the buffers in bytes.
+    vbo_capacity: usize,
+    ebo_capacity: usize,
+    usage:        gl::types::GLenum,
 }
 
 #[cfg(feature = "internal")]
@@ -75,75 +198,175 @@ impl InternalMesh {
     pub fn build(mesh: Mesh) -> Self {
         let mut vao = 0;
         let mut vbo = 0;
-        let mut ebo = None;
-
-        let flattened: Vec<f32> = mesh.vertices.iter().flat_map(|v| v.flatten()).collect();
 
         unsafe {
             gl::GenVertexArrays(1, &mut vao);
-            gl::BindVertexArray(vao);
-
             gl::GenBuffers(1, &mut vbo);
-            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
-            gl::BufferData(
-                gl::ARRAY_BUFFER,
-                (flattened.len() * F32_SIZE as usize) as isize,
-                flattened.as_ptr() as *const _,
-                gl::STATIC_DRAW,
-            );
-
-            let stride = if mesh.stride <= BASE_STRIDE { BASE_STRIDE } else { mesh.stride };
-
-            let mut offset = 0;
-            for (i, attr) in mesh.attributes.iter().enumerate() {
-                let index = i as u32;
-                let size = attr.size as i32;
-
-                gl::EnableVertexAttribArray(index);
-                gl::VertexAttribPointer(
-                    index,
-                    size,
-                    gl::FLOAT,
-                    gl::FALSE,
-                    stride as i32,
-                    (offset * F32_SIZE) as *const _,
-                );
-
-                offset += size as u32;
+        }
+        GpuObject::VertexArray.created();
+        GpuObject::Buffer.created();
+
+        let mut internal =
+            Self { mesh: Mesh::new(), vao, vbo, ebo: None, vbo_capacity: 0, ebo_capacity: 0, usage: gl::STATIC_DRAW };
+        internal.upload(mesh);
+        internal
+    }
+
+    /// Replaces the mesh data in place, keeping the GPU objects.
+    ///
+    /// Updated meshes are stored with `DYNAMIC_DRAW` and the buffers are only reallocated when the new
+    /// data does not fit, so streaming a mesh of similar size every frame is cheap.
+    pub fn update(&mut self, mesh: Mesh) {
+        if self.usage != gl::DYNAMIC_DRAW {
+            self.usage = gl::DYNAMIC_DRAW;
+            self.vbo_capacity = 0;
+            self.ebo_capacity = 0;
+        }
+        self.upload(mesh);
+    }
+
+    pub fn draw(&self) {
+        let mode = self.mesh.topology.gl_mode();
+
+        unsafe {
+            if self.mesh.topology.is_points() {
+                gl::PointSize(self.mesh.point_size);
+            } else if self.mesh.topology.is_lines() {
+                gl::LineWidth(self.mesh.line_width);
+            }
+
+            gl::BindVertexArray(self.vao);
+            if let Some(indices) = self.mesh.indices.as_ref() {
+                gl::DrawElements(mode, indices.len() as i32, gl::UNSIGNED_INT, std::ptr::null());
+            } else {
+                gl::DrawArrays(mode, 0, self.mesh.vertices.len() as i32);
+            }
+            gl::BindVertexArray(0); // Unbind the VAO
+        }
+    }
+
+    // ---------------< PRIVATE >---------------
+
+    fn upload(&mut self, mesh: Mesh) {
+        let vertex_data = mesh.vertex_data();
+
+        unsafe {
+            gl::BindVertexArray(self.vao);
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            self.vbo_capacity = Self::write_buffer(gl::ARRAY_BUFFER, &vertex_data, self.vbo_capacity, self.usage);
+
+            if mesh.attributes != self.mesh.attributes {
+                Self::set_attribute_pointers(&mesh, &self.mesh);
             }
 
             if let Some(indices) = mesh.indices.as_ref() {
-                let mut ebo_id = 0;
-                gl::GenBuffers(1, &mut ebo_id);
-                gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo_id);
-                gl::BufferData(
-                    gl::ELEMENT_ARRAY_BUFFER,
-                    (indices.len() * U32_SIZE as usize) as isize,
-                    indices.as_ptr() as *const _,
-                    gl::STATIC_DRAW,
-                );
-                ebo = Some(ebo_id);
+                let ebo = *self.ebo.get_or_insert_with(|| {
+                    let mut ebo = 0;
+                    gl::GenBuffers(1, &mut ebo);
+                    GpuObject::Buffer.created();
+                    ebo
+                });
+                gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
+                self.ebo_capacity =
+                    Self::write_buffer(gl::ELEMENT_ARRAY_BUFFER, indices, self.ebo_capacity, self.usage);
             }
 
             gl::BindVertexArray(0); // Unbind the VAO
-        };
+        }
+
+        self.mesh = mesh;
+    }
+
+    /// Writes `data` to the buffer bound to `target`, reallocating it if it is larger than `capacity`.
+    /// Returns the new capacity.
+    unsafe fn write_buffer<D>(
+        target: gl::types::GLenum,
+        data: &[D],
+        capacity: usize,
+        usage: gl::types::GLenum,
+    ) -> usize {
+        let size = size_of_val(data);
+
+        unsafe {
+            if size > capacity || capacity == 0 {
+                gl::BufferData(target, size as isize, data.as_ptr() as *const _, usage);
+                return size;
+            }
+            gl::BufferSubData(target, 0, size as isize, data.as_ptr() as *const _);
+        }
 
-        Self { mesh, vao, vbo, ebo, draw_mode: gl::TRIANGLES }
+        capacity
     }
 
-    pub fn set_draw_mode(&mut self, mode: gl::types::GLenum) {
-        self.draw_mode = mode;
+    unsafe fn set_attribute_pointers(mesh: &Mesh, previous: &Mesh) {
+        let layout = mesh.attribute_layout();
+
+        for (attr, (location, offset)) in mesh.attributes.iter().zip(&layout) {
+            let stride = mesh.vertex_stride() as i32;
+            let format = attr.format;
+
+            unsafe {
+                gl::EnableVertexAttribArray(*location);
+                if format.is_integer() {
+                    gl::VertexAttribIPointer(
+                        *location,
+                        attr.size as i32,
+                        format.gl_type(),
+                        stride,
+                        *offset as *const _,
+                    );
+                } else {
+                    gl::VertexAttribPointer(
+                        *location,
+                        attr.size as i32,
+                        format.gl_type(),
+                        if format.is_normalized() { gl::TRUE } else { gl::FALSE },
+                        stride,
+                        *offset as *const _,
+                    );
+                }
+            }
+        }
+
+        for (location, _) in previous.attribute_layout() {
+            if !layout.iter().any(|(used, _)| *used == location) {
+                unsafe {
+                    gl::DisableVertexAttribArray(location);
+                }
+            }
+        }
     }
+}
 
-    pub fn draw(&self) {
+#[cfg(feature = "internal")]
+impl Drop for InternalMesh {
+    fn drop(&mut self) {
         unsafe {
-            gl::BindVertexArray(self.vao);
-            if let Some(indices) = self.mesh.indices.as_ref() {
-                gl::DrawElements(self.draw_mode, indices.len() as i32, gl::UNSIGNED_INT, std::ptr::null());
-            } else {
-                gl::DrawArrays(self.draw_mode, 0, self.mesh.vertices.len() as i32);
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+            if let Some(ebo) = self.ebo.as_ref() {
+                gl::DeleteBuffers(1, ebo);
             }
-            gl::BindVertexArray(0); // Unbind the VAO
+        }
+
+        GpuObject::VertexArray.deleted();
+        GpuObject::Buffer.deleted();
+        if self.ebo.is_some() {
+            GpuObject::Buffer.deleted();
+        }
+    }
+}
+
+impl Default for Mesh {
+    fn default() -> Self {
+        Self {
+            vertices:   Vec::new(),
+            attributes: Vec::new(),
+            indices:    None,
+            topology:   Topology::default(),
+            point_size: 1.0,
+            line_width: 1.0,
         }
     }
 }
@@ -155,6 +378,42 @@ impl Hash for Mesh {
         self.vertices.hash(state);
         self.attributes.hash(state);
         self.indices.hash(state);
-        self.stride.hash(state);
+        self.topology.hash(state);
+        self.point_size.to_bits().hash(state);
+        self.line_width.to_bits().hash(state);
+    }
+}
+
+#[cfg(all(test, feature = "internal"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixed_formats_are_interleaved_with_padding() {
+        let id = AttributeType::Custom { name: "id".to_string(), size: 1 };
+        let flags = AttributeType::Custom { name: "flags".to_string(), size: 3 };
+        let mesh = Mesh::new()
+            .with_attribute(AttributeType::Position, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+            .with_attribute(id.clone(), vec![-7.0, 8.0])
+            .with_attribute(AttributeType::Color, vec![1.0, 0.5, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0])
+            .with_attribute(flags.clone(), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+            .with_attribute_format(&id, VertexFormat::I32)
+            .with_attribute_format(&AttributeType::Color, VertexFormat::U8Norm)
+            .with_attribute_format(&flags, VertexFormat::U8);
+
+        // 12 bytes of positions, 4 of ids, 4 of colors and 3 of flags padded to 4
+        assert_eq!(mesh.vertex_stride(), 24);
+        assert_eq!(mesh.attribute_layout(), [(0, 0), (2, 12), (1, 16), (3, 20)]);
+
+        let data = mesh.vertex_data();
+        assert_eq!(data.len(), 48);
+        assert_eq!(data[0..4], 1.0f32.to_ne_bytes());
+        assert_eq!(data[8..12], 3.0f32.to_ne_bytes());
+        assert_eq!(data[12..16], (-7i32).to_ne_bytes());
+        assert_eq!(data[16..20], [255, 128, 0, 255]);
+        assert_eq!(data[20..24], [1, 2, 3, 0]);
+        assert_eq!(data[24..28], 4.0f32.to_ne_bytes());
+        assert_eq!(data[36..40], 8i32.to_ne_bytes());
+        assert_eq!(data[44..48], [4, 5, 6, 0]);
     }
 }
@@ -1,13 +1,25 @@
 mod attribute;
+mod format;
 mod mesh;
 mod primitive;
+mod stroke;
+mod topology;
+mod triangulate;
 mod vertex;
 
 pub use attribute::*;
+pub use format::*;
 pub use mesh::*;
 pub use primitive::*;
+pub use stroke::*;
+pub use topology::*;
+pub(crate) use triangulate::*;
 pub use vertex::*;
 
 pub fn positions_from_vec3s(positions: Vec<paper_math::Vec3>) -> Vec<f32> {
     positions.into_iter().flat_map(|pos| pos.to_array().to_vec()).collect()
 }
+
+pub fn positions_from_vec2s(positions: &[paper_math::Vec2]) -> Vec<f32> {
+    positions.iter().flat_map(|pos| [pos.x, pos.y, 0.0]).collect()
+}
@@ -1,22 +1,92 @@
-#[cfg(feature = "internal")]
-use std::os::raw::c_uint;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
-use paper_math::{Transform, Vec2};
+use paper_math::{Rect, Transform, Vec2};
 
-use crate::{AttributeType, Mesh};
+use crate::{AttributeType, Mesh, Stroke, Topology, positions_from_vec2s, triangulate};
 
-const TRIANGLE_VERTICES: [f32; 9] = [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0];
-const RECTANGLE_VERTICES: [f32; 12] = [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.5, 0.5, 0.0, -0.5, 0.5, 0.0];
+pub const UV_ATTRIBUTE: &str = "uv";
+
+const TRIANGLE_VERTICES: [Vec2; 3] = [Vec2::new(-0.5, -0.5), Vec2::new(0.5, -0.5), Vec2::new(0.0, 0.5)];
+const RECTANGLE_VERTICES: [Vec2; 4] =
+    [Vec2::new(-0.5, -0.5), Vec2::new(0.5, -0.5), Vec2::new(0.5, 0.5), Vec2::new(-0.5, 0.5)];
 
 const TRIANGLE_INDICES: [u32; 3] = [0, 1, 2];
 const RECTANGLE_INDICES: [u32; 6] = [0, 1, 3, 1, 2, 3];
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// A 2D shape centered on `pos`. Angles are in radians, counter clockwise from the positive x axis.
+///
+/// Shapes that only depend on their segment count are built as unit meshes and scaled by their
+/// [`transform`](Shape2D::transform), so primitives of the same kind can share a mesh.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Shape2D {
-    Triangle { pos: Vec2, size: Vec2 },
-    Rectangle { pos: Vec2, size: Vec2 },
-    Square { pos: Vec2, size: f32 },
-    Circle { pos: Vec2, radius: f32, segments: u32 },
+    Triangle {
+        pos:  Vec2,
+        size: Vec2,
+    },
+    Rectangle {
+        pos:  Vec2,
+        size: Vec2,
+    },
+    Square {
+        pos:  Vec2,
+        size: f32,
+    },
+    Circle {
+        pos:      Vec2,
+        radius:   f32,
+        segments: u32,
+    },
+    /// Regular polygon with its first corner pointing up.
+    Ngon {
+        pos:    Vec2,
+        radius: f32,
+        sides:  u32,
+    },
+    /// Simple polygon, convex or concave, with its points relative to `pos`.
+    Polygon {
+        pos:    Vec2,
+        points: Vec<Vec2>,
+    },
+    /// `segments` is the number of segments per corner.
+    RoundedRectangle {
+        pos:      Vec2,
+        size:     Vec2,
+        radius:   f32,
+        segments: u32,
+    },
+    Ellipse {
+        pos:      Vec2,
+        radii:    Vec2,
+        segments: u32,
+    },
+    /// Pie slice from `start` to `end`.
+    Arc {
+        pos:      Vec2,
+        radius:   f32,
+        start:    f32,
+        end:      f32,
+        segments: u32,
+    },
+    Ring {
+        pos:          Vec2,
+        inner_radius: f32,
+        outer_radius: f32,
+        segments:     u32,
+    },
+    /// Horizontal capsule, `length` is the distance between the centers of its caps.
+    Capsule {
+        pos:      Vec2,
+        length:   f32,
+        radius:   f32,
+        segments: u32,
+    },
+    /// Star with its first tip pointing up.
+    Star {
+        pos:          Vec2,
+        points:       u32,
+        inner_radius: f32,
+        outer_radius: f32,
+    },
 }
 
 impl Shape2D {
@@ -36,74 +106,254 @@ impl Shape2D {
         Shape2D::Circle { pos, radius, segments }
     }
 
-    pub fn vertices(&self) -> Vec<f32> {
+    pub fn ngon(pos: Vec2, radius: f32, sides: u32) -> Self {
+        Shape2D::Ngon { pos, radius, sides }
+    }
+
+    pub fn polygon(pos: Vec2, points: impl Into<Vec<Vec2>>) -> Self {
+        Shape2D::Polygon { pos, points: points.into() }
+    }
+
+    pub fn rounded_rectangle(pos: Vec2, size: Vec2, radius: f32, segments: u32) -> Self {
+        Shape2D::RoundedRectangle { pos, size, radius, segments }
+    }
+
+    pub fn ellipse(pos: Vec2, radii: Vec2, segments: u32) -> Self {
+        Shape2D::Ellipse { pos, radii, segments }
+    }
+
+    pub fn arc(pos: Vec2, radius: f32, start: f32, end: f32, segments: u32) -> Self {
+        Shape2D::Arc { pos, radius, start, end, segments }
+    }
+
+    pub fn ring(pos: Vec2, inner_radius: f32, outer_radius: f32, segments: u32) -> Self {
+        Shape2D::Ring { pos, inner_radius, outer_radius, segments }
+    }
+
+    pub fn capsule(pos: Vec2, length: f32, radius: f32, segments: u32) -> Self {
+        Shape2D::Capsule { pos, length, radius, segments }
+    }
+
+    pub fn star(pos: Vec2, points: u32, inner_radius: f32, outer_radius: f32) -> Self {
+        Shape2D::Star { pos, points, inner_radius, outer_radius }
+    }
+
+    pub fn pos(&self) -> Vec2 {
         match self {
-            Shape2D::Triangle { .. } => TRIANGLE_VERTICES.to_vec(),
-            Shape2D::Rectangle { .. } => RECTANGLE_VERTICES.to_vec(),
-            Shape2D::Square { .. } => RECTANGLE_VERTICES.to_vec(),
-            Shape2D::Circle { segments, .. } => {
-                let mut vertices = Vec::new();
-                for i in 0..*segments {
-                    let angle = (i as f32 / *segments as f32) * std::f32::consts::PI * 2.0;
-                    vertices.push(angle.cos());
-                    vertices.push(angle.sin());
-                    vertices.push(0.0);
-                }
-                vertices
-            }
+            Shape2D::Triangle { pos, .. }
+            | Shape2D::Rectangle { pos, .. }
+            | Shape2D::Square { pos, .. }
+            | Shape2D::Circle { pos, .. }
+            | Shape2D::Ngon { pos, .. }
+            | Shape2D::Polygon { pos, .. }
+            | Shape2D::RoundedRectangle { pos, .. }
+            | Shape2D::Ellipse { pos, .. }
+            | Shape2D::Arc { pos, .. }
+            | Shape2D::Ring { pos, .. }
+            | Shape2D::Capsule { pos, .. }
+            | Shape2D::Star { pos, .. } => *pos,
         }
     }
 
+    pub fn vertices(&self) -> Vec<f32> {
+        positions_from_vec2s(&self.geometry().positions)
+    }
+
     pub fn indices(&self) -> Vec<u32> {
-        match self {
-            Shape2D::Triangle { .. } => TRIANGLE_INDICES.to_vec(),
-            Shape2D::Rectangle { .. } => RECTANGLE_INDICES.to_vec(),
-            Shape2D::Square { .. } => RECTANGLE_INDICES.to_vec(),
-            Shape2D::Circle { segments, .. } => {
-                let mut indices = Vec::new();
-                for i in 0..*segments {
-                    indices.push(0);
-                    indices.push((i + 1) % *segments);
-                    indices.push(i);
-                }
-                indices
-            }
-        }
+        self.geometry().indices
+    }
+
+    /// Texture coordinates spanning the bounds of the shape, `(0, 0)` is the bottom left corner.
+    pub fn uvs(&self) -> Vec<f32> {
+        let geometry = self.geometry();
+        let bounds = Rect::from_points(geometry.positions.iter().copied());
+        let size = bounds.size();
+
+        geometry
+            .positions
+            .iter()
+            .flat_map(|p| {
+                let uv = (*p - bounds.min) / size;
+                [if size.x > 0.0 { uv.x } else { 0.5 }, if size.y > 0.0 { uv.y } else { 0.5 }]
+            })
+            .collect()
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.geometry().topology
+    }
+
+    /// Bounds of the shape relative to `pos`, with the scale of its transform applied.
+    pub fn bounds(&self) -> Rect {
+        let scale = self.scale();
+        Rect::from_points(self.geometry().positions.into_iter().map(|p| p * scale))
     }
 
     pub fn mesh(&self) -> Mesh {
-        let vertices = self.vertices();
-        let indices = self.indices();
+        let topology = self.topology();
 
-        Mesh::new().with_attribute(AttributeType::Position, vertices).with_indices(indices)
+        Mesh::new()
+            .with_attribute(AttributeType::Position, self.vertices())
+            .with_attribute(AttributeType::uv(), self.uvs())
+            .with_indices(self.indices())
+            .with_topology(topology)
     }
 
     pub fn transform(&self) -> Transform {
+        Transform::from_translation(self.pos().extend(0.0)).with_scale(self.scale().extend(1.0))
+    }
+
+    /// Scale applied by the [`transform`](Shape2D::transform) of the shape to its unit mesh.
+    fn scale(&self) -> Vec2 {
         match self {
-            Shape2D::Triangle { pos, size } => {
-                Transform::from_translation(pos.extend(0.0)).with_scale(size.extend(1.0))
-            }
-            Shape2D::Rectangle { pos, size } => {
-                Transform::from_translation(pos.extend(0.0)).with_scale(size.extend(1.0))
-            }
-            Shape2D::Square { pos, size } => {
-                Transform::from_translation(pos.extend(0.0)).with_scale(Vec2::splat(*size).extend(1.0))
-            }
-            Shape2D::Circle { pos, radius, segments: _ } => {
-                Transform::from_translation(pos.extend(0.0)).with_scale(Vec2::splat(*radius).extend(1.0))
+            Shape2D::Triangle { size, .. } | Shape2D::Rectangle { size, .. } => *size,
+            Shape2D::Square { size, .. } => Vec2::splat(*size),
+            Shape2D::Circle { radius, .. } | Shape2D::Ngon { radius, .. } | Shape2D::Arc { radius, .. } => {
+                Vec2::splat(*radius)
             }
+            Shape2D::Ellipse { radii, .. } => *radii,
+            _ => Vec2::ONE,
         }
     }
 
-    #[cfg(feature = "internal")]
-    pub fn draw_mode(&self) -> c_uint {
-        use glad_gl::gl;
+    /// The closed outlines of the shape relative to `pos`, with the scale of its transform applied.
+    /// Rings have an outer and an inner outline, every other shape has at most one.
+    pub fn contours(&self) -> Vec<Vec<Vec2>> {
+        let scale = self.scale();
+        let geometry = self.geometry();
+        if geometry.topology == Topology::Points {
+            return Vec::new();
+        }
 
+        let positions: Vec<Vec2> = geometry.positions.into_iter().map(|p| p * scale).collect();
         match self {
-            Shape2D::Triangle { .. } => gl::TRIANGLES,
-            Shape2D::Rectangle { .. } => gl::TRIANGLES,
-            Shape2D::Circle { segments, .. } if *segments > 0 => gl::TRIANGLE_FAN,
-            _ => gl::POINTS, // Default to points for circles with no segments
+            // Pie slices include their center
+            Shape2D::Triangle { .. }
+            | Shape2D::Rectangle { .. }
+            | Shape2D::Square { .. }
+            | Shape2D::Polygon { .. }
+            | Shape2D::Arc { .. } => vec![positions],
+            Shape2D::Ring { .. } => vec![
+                positions.iter().step_by(2).copied().collect(),
+                positions.iter().skip(1).step_by(2).copied().collect(),
+            ],
+            // Fans start with their center
+            _ => vec![positions[1..].to_vec()],
+        }
+    }
+
+    /// The outline of the shape stroked with `stroke`, meant to be drawn with
+    /// [`outline_transform`](Shape2D::outline_transform) so the stroke width is not scaled.
+    pub fn outline_mesh(&self, stroke: &Stroke) -> Mesh {
+        let (mut positions, mut indices) = (Vec::new(), Vec::new());
+        for contour in self.contours() {
+            let (contour_positions, contour_indices) = stroke.tessellate(&contour, true);
+            let base = positions.len() as u32;
+            positions.extend(contour_positions);
+            indices.extend(contour_indices.into_iter().map(|i| base + i));
+        }
+
+        Mesh::new()
+            .with_attribute(AttributeType::Position, positions_from_vec2s(&positions))
+            .with_indices(indices)
+            .with_topology(Topology::Triangles)
+    }
+
+    pub fn outline_transform(&self) -> Transform {
+        Transform::from_translation(self.pos().extend(0.0))
+    }
+
+    // ---------------< PRIVATE >---------------
+
+    fn geometry(&self) -> Geometry {
+        match self {
+            Shape2D::Triangle { .. } => Geometry {
+                positions: TRIANGLE_VERTICES.to_vec(),
+                indices:   TRIANGLE_INDICES.to_vec(),
+                topology:  Topology::Triangles,
+            },
+            Shape2D::Rectangle { .. } | Shape2D::Square { .. } => Geometry {
+                positions: RECTANGLE_VERTICES.to_vec(),
+                indices:   RECTANGLE_INDICES.to_vec(),
+                topology:  Topology::Triangles,
+            },
+            Shape2D::Circle { segments, .. } | Shape2D::Ellipse { segments, .. } => {
+                Geometry::fan((0..*segments).map(|i| unit(i as f32 / *segments as f32 * TAU)).collect())
+            }
+            Shape2D::Ngon { sides, .. } => {
+                Geometry::fan((0..*sides).map(|i| unit(FRAC_PI_2 + i as f32 / *sides as f32 * TAU)).collect())
+            }
+            Shape2D::Polygon { points, .. } => {
+                let mut points = points.clone();
+                if points.len() > 1 && points.first() == points.last() {
+                    points.pop();
+                }
+                let indices = triangulate(&points);
+                Geometry { positions: points, indices, topology: Topology::Triangles }
+            }
+            Shape2D::RoundedRectangle { size, radius, segments, .. } => {
+                let half = size.abs() * 0.5;
+                let radius = if *segments == 0 { 0.0 } else { radius.clamp(0.0, half.min_element()) };
+                let inner = half - radius;
+
+                let mut outline = Vec::new();
+                for (corner, center) in
+                    [inner, inner * Vec2::new(-1.0, 1.0), -inner, inner * Vec2::new(1.0, -1.0)].into_iter().enumerate()
+                {
+                    if radius <= 0.0 {
+                        outline.push(center);
+                        continue;
+                    }
+                    let start = corner as f32 * FRAC_PI_2;
+                    outline.extend(arc_points(center, radius, start, start + FRAC_PI_2, *segments));
+                }
+                Geometry::fan(outline)
+            }
+            Shape2D::Arc { start, end, segments, .. } => {
+                if *segments == 0 {
+                    return Geometry::fan(Vec::new());
+                }
+                let outline = arc_points(Vec2::ZERO, 1.0, *start, *end, *segments);
+                let indices = (0..=outline.len() as u32).collect();
+                Geometry {
+                    positions: [Vec2::ZERO].into_iter().chain(outline).collect(),
+                    indices,
+                    topology: Topology::TriangleFan,
+                }
+            }
+            Shape2D::Ring { inner_radius, outer_radius, segments, .. } => {
+                if *segments == 0 {
+                    return Geometry::fan(Vec::new());
+                }
+                let positions: Vec<Vec2> = (0..*segments)
+                    .flat_map(|i| {
+                        let direction = unit(i as f32 / *segments as f32 * TAU);
+                        [direction * *outer_radius, direction * *inner_radius]
+                    })
+                    .collect();
+                // Returns to the first pair of vertices to close the strip
+                let indices = (0..positions.len() as u32).chain([0, 1]).collect();
+                Geometry { positions, indices, topology: Topology::TriangleStrip }
+            }
+            Shape2D::Capsule { length, radius, segments, .. } => {
+                let half = length.abs() * 0.5;
+                let segments = (*segments).max(1);
+
+                let mut outline = arc_points(Vec2::new(half, 0.0), *radius, -FRAC_PI_2, FRAC_PI_2, segments);
+                outline.extend(arc_points(Vec2::new(-half, 0.0), *radius, FRAC_PI_2, PI + FRAC_PI_2, segments));
+                Geometry::fan(outline)
+            }
+            Shape2D::Star { points, inner_radius, outer_radius, .. } => {
+                let corners = points * 2;
+                Geometry::fan(
+                    (0..corners)
+                        .map(|i| {
+                            let radius = if i % 2 == 0 { outer_radius } else { inner_radius };
+                            unit(FRAC_PI_2 + i as f32 / corners as f32 * TAU) * *radius
+                        })
+                        .collect(),
+                )
+            }
         }
     }
 }
@@ -131,3 +381,131 @@ impl From<&Shape2D> for Transform {
         shape.transform()
     }
 }
+
+// ---------------< PRIVATE >---------------
+
+struct Geometry {
+    positions: Vec<Vec2>,
+    indices:   Vec<u32>,
+    topology:  Topology,
+}
+
+impl Geometry {
+    /// A triangle fan around the origin for a convex or star shaped outline. Shapes without an
+    /// outline collapse into a single point at their center.
+    fn fan(outline: Vec<Vec2>) -> Self {
+        if outline.is_empty() {
+            return Self { positions: vec![Vec2::ZERO], indices: vec![0], topology: Topology::Points };
+        }
+
+        // Closes the fan by returning to the first outline vertex
+        let indices = (0..=outline.len() as u32).chain([1]).collect();
+        Self { positions: [Vec2::ZERO].into_iter().chain(outline).collect(), indices, topology: Topology::TriangleFan }
+    }
+}
+
+fn unit(angle: f32) -> Vec2 {
+    Vec2::from_angle(angle)
+}
+
+fn arc_points(center: Vec2, radius: f32, start: f32, end: f32, segments: u32) -> Vec<Vec2> {
+    (0..=segments).map(|i| center + unit(start + (end - start) * i as f32 / segments as f32) * radius).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shapes() -> Vec<Shape2D> {
+        vec![
+            Shape2D::triangle(Vec2::ZERO, Vec2::new(2.0, 1.0)),
+            Shape2D::rectangle(Vec2::ZERO, Vec2::new(2.0, 1.0)),
+            Shape2D::square(Vec2::ZERO, 2.0),
+            Shape2D::circle(Vec2::ZERO, 2.0, 16),
+            Shape2D::ngon(Vec2::ZERO, 2.0, 5),
+            Shape2D::polygon(Vec2::ZERO, [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::new(0.5, 0.5), Vec2::Y]),
+            Shape2D::rounded_rectangle(Vec2::ZERO, Vec2::new(4.0, 2.0), 0.5, 4),
+            Shape2D::ellipse(Vec2::ZERO, Vec2::new(3.0, 1.0), 16),
+            Shape2D::arc(Vec2::ZERO, 2.0, 0.0, FRAC_PI_2, 8),
+            Shape2D::ring(Vec2::ZERO, 1.0, 2.0, 16),
+            Shape2D::capsule(Vec2::ZERO, 4.0, 1.0, 8),
+            Shape2D::star(Vec2::ZERO, 5, 0.8, 2.0),
+        ]
+    }
+
+    fn assert_rect_eq(a: Rect, b: Rect) {
+        assert!(a.min.abs_diff_eq(b.min, 1e-5) && a.max.abs_diff_eq(b.max, 1e-5), "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn indices_and_uvs_match_the_vertices() {
+        for shape in shapes() {
+            let count = shape.vertices().len() / 3;
+
+            assert_eq!(shape.uvs().len(), count * 2, "{shape:?}");
+            assert!(shape.indices().iter().all(|&i| (i as usize) < count), "{shape:?}");
+        }
+    }
+
+    #[test]
+    fn uvs_span_the_bounds() {
+        for shape in shapes() {
+            let uvs: Vec<Vec2> = shape.uvs().chunks(2).map(|uv| Vec2::new(uv[0], uv[1])).collect();
+            let bounds = Rect::from_points(uvs.iter().copied());
+            assert_rect_eq(bounds, Rect::new(Vec2::ZERO, Vec2::ONE));
+        }
+
+        let rectangle = Shape2D::rectangle(Vec2::ZERO, Vec2::ONE).uvs();
+        assert_eq!(rectangle, [0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn bounds() {
+        let expected = [
+            Rect::new(Vec2::new(-1.0, -0.5), Vec2::new(1.0, 0.5)),
+            Rect::new(Vec2::new(-1.0, -0.5), Vec2::new(1.0, 0.5)),
+            Rect::new(Vec2::splat(-1.0), Vec2::splat(1.0)),
+            Rect::new(Vec2::splat(-2.0), Vec2::splat(2.0)),
+            Rect::new(Vec2::new(-2.0 * 0.951_056_5, -2.0 * 0.809_017), Vec2::new(2.0 * 0.951_056_5, 2.0)),
+            Rect::new(Vec2::ZERO, Vec2::ONE),
+            Rect::new(Vec2::new(-2.0, -1.0), Vec2::new(2.0, 1.0)),
+            Rect::new(Vec2::new(-3.0, -1.0), Vec2::new(3.0, 1.0)),
+            Rect::new(Vec2::ZERO, Vec2::splat(2.0)),
+            Rect::new(Vec2::splat(-2.0), Vec2::splat(2.0)),
+            Rect::new(Vec2::new(-3.0, -1.0), Vec2::new(3.0, 1.0)),
+            Rect::new(Vec2::new(-2.0 * 0.951_056_5, -2.0 * 0.809_017), Vec2::new(2.0 * 0.951_056_5, 2.0)),
+        ];
+
+        for (shape, expected) in shapes().into_iter().zip(expected) {
+            assert_rect_eq(shape.bounds(), expected);
+        }
+    }
+
+    #[test]
+    fn polygon_drops_repeated_closing_point() {
+        let square = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::ZERO];
+        let polygon = Shape2D::polygon(Vec2::new(5.0, 5.0), square);
+
+        assert_eq!(polygon.vertices().len(), 4 * 3);
+        assert_eq!(polygon.indices().len(), 6);
+        assert_eq!(polygon.contours(), vec![square[..4].to_vec()]);
+    }
+
+    #[test]
+    fn ring_has_two_contours() {
+        let contours = Shape2D::ring(Vec2::ZERO, 1.0, 2.0, 8).contours();
+
+        assert_eq!(contours.len(), 2);
+        assert!(contours[0].iter().all(|p| (p.length() - 2.0).abs() < 1e-5));
+        assert!(contours[1].iter().all(|p| (p.length() - 1.0).abs() < 1e-5));
+    }
+
+    #[test]
+    fn shapes_without_segments_collapse_to_a_point() {
+        for shape in [Shape2D::arc(Vec2::ZERO, 1.0, 0.0, PI, 0), Shape2D::ring(Vec2::ZERO, 1.0, 2.0, 0)] {
+            assert_eq!(shape.topology(), Topology::Points);
+            assert_eq!(shape.uvs(), [0.5, 0.5]);
+            assert!(shape.contours().is_empty());
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use std::f32::consts::PI;
+
+use paper_math::Vec2;
+
+use crate::{AttributeType, Mesh, Topology, positions_from_vec2s};
+
+/// Maximum angle covered by a single triangle of a round join or cap.
+const ROUND_STEP: f32 = PI / 12.0;
+const EPSILON: f32 = 1e-6;
+
+/// How two segments of a stroke are connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LineJoin {
+    /// Extends the outer edges until they meet, falling back to [`LineJoin::Bevel`] past the miter limit.
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// How the ends of an open stroke are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LineCap {
+    /// Ends exactly at the end points.
+    #[default]
+    Butt,
+    Round,
+    /// Extends past the end points by half the width.
+    Square,
+}
+
+/// Turns polylines into triangle geometry of the given width, centered on the line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    pub width:       f32,
+    pub join:        LineJoin,
+    pub cap:         LineCap,
+    /// Ratio of miter length to half the width above which miter joins are beveled.
+    pub miter_limit: f32,
+}
+
+impl Stroke {
+    pub fn new(width: f32) -> Self {
+        Self { width, ..Default::default() }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    /// Triangles covering the stroked polyline. Closed polylines connect their last point to the
+    /// first one and have no caps.
+    pub fn tessellate(&self, points: &[Vec2], closed: bool) -> (Vec<Vec2>, Vec<u32>) {
+        let mut builder =
+            Builder { half_width: self.width.abs() * 0.5, positions: Vec::new(), indices: Vec::new() };
+
+        let mut points = dedup(points);
+        let closed = closed && points.len() > 2;
+        if closed && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 2 || builder.half_width <= 0.0 {
+            return (Vec::new(), Vec::new());
+        }
+
+        let count = points.len();
+        let segments = if closed { count } else { count - 1 };
+
+        for i in 0..segments {
+            let mut start = points[i];
+            let mut end = points[(i + 1) % count];
+            let direction = (end - start).normalize();
+
+            if !closed && self.cap == LineCap::Square {
+                if i == 0 {
+                    start -= direction * builder.half_width;
+                }
+                if i == segments - 1 {
+                    end += direction * builder.half_width;
+                }
+            }
+            builder.segment(start, end, direction);
+        }
+
+        let joints = if closed { 0..count } else { 1..count - 1 };
+        for i in joints {
+            let prev = points[(i + count - 1) % count];
+            let point = points[i];
+            let next = points[(i + 1) % count];
+            self.join(&mut builder, point, (point - prev).normalize(), (next - point).normalize());
+        }
+
+        if !closed && self.cap == LineCap::Round {
+            let first = (points[1] - points[0]).normalize();
+            let last = (points[count - 1] - points[count - 2]).normalize();
+            builder.round(points[0], first.perp(), PI);
+            builder.round(points[count - 1], -last.perp(), PI);
+        }
+
+        (builder.positions, builder.indices)
+    }
+
+    pub fn mesh(&self, points: &[Vec2], closed: bool) -> Mesh {
+        let (positions, indices) = self.tessellate(points, closed);
+
+        Mesh::new()
+            .with_attribute(AttributeType::Position, positions_from_vec2s(&positions))
+            .with_indices(indices)
+            .with_topology(Topology::Triangles)
+    }
+
+    // ---------------< PRIVATE >---------------
+
+    fn join(&self, builder: &mut Builder, point: Vec2, incoming: Vec2, outgoing: Vec2) {
+        let turn = incoming.perp_dot(outgoing);
+        if turn.abs() <= EPSILON && incoming.dot(outgoing) > 0.0 {
+            return;
+        }
+
+        // The outer side of a left turn is on the right of the line
+        let side = if turn > 0.0 { -1.0 } else { 1.0 };
+        let from = incoming.perp() * side;
+        let to = outgoing.perp() * side;
+
+        match self.join {
+            LineJoin::Round => builder.round(point, from, from.angle_to(to)),
+            LineJoin::Miter => {
+                let bisector = (from + to).normalize_or_zero();
+                let cos = bisector.dot(from);
+                if cos > EPSILON && 1.0 / cos <= self.miter_limit {
+                    let tip = point + bisector * (builder.half_width / cos);
+                    builder.triangle(point, point + from * builder.half_width, tip);
+                    builder.triangle(point, tip, point + to * builder.half_width);
+                } else {
+                    builder.triangle(point, point + from * builder.half_width, point + to * builder.half_width);
+                }
+            }
+            LineJoin::Bevel => {
+                builder.triangle(point, point + from * builder.half_width, point + to * builder.half_width);
+            }
+        }
+    }
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self { width: 1.0, join: LineJoin::default(), cap: LineCap::default(), miter_limit: 4.0 }
+    }
+}
+
+// ---------------< PRIVATE >---------------
+
+struct Builder {
+    half_width: f32,
+    positions:  Vec<Vec2>,
+    indices:    Vec<u32>,
+}
+
+impl Builder {
+    fn segment(&mut self, start: Vec2, end: Vec2, direction: Vec2) {
+        let offset = direction.perp() * self.half_width;
+        let base = self.positions.len() as u32;
+
+        self.positions.extend([start - offset, end - offset, end + offset, start + offset]);
+        self.indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    fn triangle(&mut self, a: Vec2, b: Vec2, c: Vec2) {
+        let base = self.positions.len() as u32;
+
+        self.positions.extend([a, b, c]);
+        self.indices.extend([base, base + 1, base + 2]);
+    }
+
+    /// A fan around `center` sweeping `angle` radians, starting in the given direction.
+    fn round(&mut self, center: Vec2, from: Vec2, angle: f32) {
+        let steps = (angle.abs() / ROUND_STEP).ceil().max(1.0) as u32;
+        let base = self.positions.len() as u32;
+
+        self.positions.push(center);
+        self.positions.extend(
+            (0..=steps)
+                .map(|i| center + Vec2::from_angle(angle * i as f32 / steps as f32).rotate(from) * self.half_width),
+        );
+        self.indices.extend((1..=steps).flat_map(|i| [base, base + i, base + i + 1]));
+    }
+}
+
+fn dedup(points: &[Vec2]) -> Vec<Vec2> {
+    let mut points = points.to_vec();
+    points.dedup_by(|a, b| a.distance_squared(*b) <= EPSILON * EPSILON);
+    points
+}
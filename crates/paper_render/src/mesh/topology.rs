@@ -0,0 +1,37 @@
+/// How the vertices of a [`Mesh`](crate::Mesh) are assembled into primitives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Topology {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    #[default]
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+}
+
+impl Topology {
+    pub fn is_points(&self) -> bool {
+        matches!(self, Topology::Points)
+    }
+
+    pub fn is_lines(&self) -> bool {
+        matches!(self, Topology::Lines | Topology::LineStrip | Topology::LineLoop)
+    }
+
+    #[cfg(feature = "internal")]
+    pub fn gl_mode(&self) -> glad_gl::gl::types::GLenum {
+        use glad_gl::gl;
+
+        match self {
+            Topology::Points => gl::POINTS,
+            Topology::Lines => gl::LINES,
+            Topology::LineStrip => gl::LINE_STRIP,
+            Topology::LineLoop => gl::LINE_LOOP,
+            Topology::Triangles => gl::TRIANGLES,
+            Topology::TriangleStrip => gl::TRIANGLE_STRIP,
+            Topology::TriangleFan => gl::TRIANGLE_FAN,
+        }
+    }
+}
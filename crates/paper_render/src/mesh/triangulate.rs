@@ -0,0 +1,175 @@
+use log::warn;
+use paper_math::{Rect, Vec2};
+
+/// Tolerance for collinear corners, relative to the squared extent of the polygon.
+const EPSILON: f32 = 1e-6;
+
+/// Triangulates a simple polygon, convex or concave, by ear clipping.
+///
+/// The outline may be wound either way and must not repeat its first point at the end. Returns
+/// triangle indices into `points`, wound counter clockwise.
+pub(crate) fn triangulate(points: &[Vec2]) -> Vec<u32> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let extent = Rect::from_points(points.iter().copied()).size().max_element();
+    let epsilon = EPSILON * extent * extent;
+
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    if signed_area(points) < 0.0 {
+        remaining.reverse();
+    }
+
+    let mut indices = Vec::with_capacity((points.len() - 2) * 3);
+    while remaining.len() > 3 {
+        let Some((ear, degenerate)) = find_ear(points, &remaining, epsilon) else {
+            warn!("Failed to triangulate polygon with {} points, it may be self intersecting", points.len());
+            break;
+        };
+
+        let count = remaining.len();
+        if !degenerate {
+            let prev = remaining[(ear + count - 1) % count];
+            let next = remaining[(ear + 1) % count];
+            indices.extend([prev as u32, remaining[ear] as u32, next as u32]);
+        }
+        remaining.remove(ear);
+    }
+
+    if remaining.len() == 3 && cross(points[remaining[0]], points[remaining[1]], points[remaining[2]]) > epsilon {
+        indices.extend(remaining.iter().map(|&i| i as u32));
+    }
+
+    indices
+}
+
+/// Twice the signed area, positive for counter clockwise outlines.
+pub(crate) fn signed_area(points: &[Vec2]) -> f32 {
+    points.iter().zip(points.iter().cycle().skip(1)).map(|(a, b)| a.perp_dot(*b)).sum()
+}
+
+// ---------------< PRIVATE >---------------
+
+/// Position of the next ear in `remaining` and whether it is a collinear vertex that can be dropped.
+fn find_ear(points: &[Vec2], remaining: &[usize], epsilon: f32) -> Option<(usize, bool)> {
+    let count = remaining.len();
+
+    (0..count).find_map(|i| {
+        let prev = points[remaining[(i + count - 1) % count]];
+        let current = points[remaining[i]];
+        let next = points[remaining[(i + 1) % count]];
+
+        let turn = cross(prev, current, next);
+        if turn.abs() <= epsilon {
+            return Some((i, true));
+        }
+        if turn < 0.0 {
+            return None;
+        }
+
+        let blocked = remaining
+            .iter()
+            .map(|&j| points[j])
+            .any(|point| point != prev && point != current && point != next && in_triangle(point, prev, current, next));
+
+        (!blocked).then_some((i, false))
+    })
+}
+
+fn cross(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    (b - a).perp_dot(c - b)
+}
+
+fn in_triangle(point: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    cross(a, b, point) >= 0.0 && cross(b, c, point) >= 0.0 && cross(c, a, point) >= 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const L_SHAPE: [Vec2; 6] = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(2.0, 0.0),
+        Vec2::new(2.0, 1.0),
+        Vec2::new(1.0, 1.0),
+        Vec2::new(1.0, 2.0),
+        Vec2::new(0.0, 2.0),
+    ];
+
+    /// Checks that every triangle is counter clockwise and returns their total area.
+    fn triangulated_area(points: &[Vec2], indices: &[u32]) -> f32 {
+        assert_eq!(indices.len() % 3, 0);
+        indices
+            .chunks(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| points[triangle[i] as usize]);
+                let turn = cross(a, b, c);
+                assert!(turn > 0.0, "triangle {triangle:?} is not counter clockwise");
+                turn * 0.5
+            })
+            .sum()
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() <= b.abs() * 1e-4, "{a} != {b}");
+    }
+
+    #[test]
+    fn convex() {
+        let square = [Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0)];
+        let indices = triangulate(&square);
+
+        assert_eq!(indices.len(), 6);
+        assert_close(triangulated_area(&square, &indices), 1.0);
+    }
+
+    #[test]
+    fn concave() {
+        let indices = triangulate(&L_SHAPE);
+        assert_eq!(indices.len(), 12);
+        assert_close(triangulated_area(&L_SHAPE, &indices), 3.0);
+
+        let star: Vec<Vec2> = (0..10)
+            .map(|i| Vec2::from_angle(i as f32 / 10.0 * std::f32::consts::TAU) * if i % 2 == 0 { 2.0 } else { 0.8 })
+            .collect();
+        let indices = triangulate(&star);
+        assert_eq!(indices.len(), 24);
+        assert_close(triangulated_area(&star, &indices), signed_area(&star) * 0.5);
+    }
+
+    #[test]
+    fn clockwise() {
+        let mut clockwise = L_SHAPE;
+        clockwise.reverse();
+        assert!(signed_area(&clockwise) < 0.0);
+
+        let indices = triangulate(&clockwise);
+        assert_eq!(indices.len(), 12);
+        assert_close(triangulated_area(&clockwise, &indices), 3.0);
+    }
+
+    #[test]
+    fn collinear_points() {
+        let square =
+            [Vec2::new(0.0, 0.0), Vec2::new(0.5, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0)];
+        // No triangle may be degenerate, which triangulated_area checks
+        assert_close(triangulated_area(&square, &triangulate(&square)), 1.0);
+
+        let line = [Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0)];
+        assert!(triangulate(&line).is_empty());
+        assert!(triangulate(&line[..2]).is_empty());
+    }
+
+    #[test]
+    fn tolerance_scales_with_the_polygon() {
+        for scale in [1e-4, 1e4] {
+            let points = L_SHAPE.map(|p| p * scale);
+            let indices = triangulate(&points);
+
+            assert_eq!(indices.len(), 12, "scale {scale}");
+            assert_close(triangulated_area(&points, &indices), 3.0 * scale * scale);
+        }
+    }
+}
@@ -37,6 +37,17 @@ impl Vertex {
         }
     }
 
+    /// Data of the given attribute, positions and colors are always present.
+    pub fn attribute(&self, attribute_type: &AttributeType) -> Option<&[f32]> {
+        match attribute_type {
+            AttributeType::Position => Some(&self.position),
+            AttributeType::Color => Some(&self.color),
+            AttributeType::Custom { name, .. } => {
+                self.custom.iter().find(|(custom, _)| custom == name).map(|(_, data)| data.as_slice())
+            }
+        }
+    }
+
     pub fn flatten(&self) -> Vec<f32> {
         let mut flat = Vec::new();
         flat.extend_from_slice(&self.position);
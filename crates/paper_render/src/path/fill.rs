@@ -0,0 +1,206 @@
+use paper_math::Vec2;
+
+const EPSILON: f32 = 1e-6;
+
+/// Decides which regions of overlapping or self intersecting contours are inside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FillRule {
+    /// Inside where the contours wind around a point a non zero number of times.
+    #[default]
+    NonZero,
+    /// Inside where a ray from a point crosses an odd number of edges.
+    EvenOdd,
+}
+
+impl FillRule {
+    pub fn is_inside(&self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// Triangulates the area enclosed by `contours`, which are implicitly closed.
+///
+/// The plane is cut into horizontal slabs at every vertex and edge intersection. Edges do not cross
+/// within a slab, so each slab is filled with trapezoids between the edges whose accumulated winding
+/// is inside according to `rule`. This handles holes, overlaps and self intersections.
+pub(crate) fn fill(contours: &[Vec<Vec2>], rule: FillRule) -> (Vec<Vec2>, Vec<u32>) {
+    let edges: Vec<Edge> = contours
+        .iter()
+        .filter(|contour| contour.len() > 2)
+        .flat_map(|contour| contour.iter().zip(contour.iter().cycle().skip(1)).filter_map(|(a, b)| Edge::new(*a, *b)))
+        .collect();
+
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+
+    let breaks = slab_breaks(&edges);
+    for slab in breaks.windows(2) {
+        let (top, bottom) = (slab[0], slab[1]);
+        if bottom - top <= EPSILON {
+            continue;
+        }
+
+        // Every end point is a break, so edges spanning the middle of a slab span all of it
+        let middle = (top + bottom) * 0.5;
+        let mut crossing: Vec<&Edge> = edges.iter().filter(|edge| edge.min.y < middle && edge.max.y > middle).collect();
+        crossing.sort_by(|a, b| a.x_at(middle).total_cmp(&b.x_at(middle)));
+
+        let mut winding = 0;
+        for pair in crossing.windows(2) {
+            winding += pair[0].winding;
+            if !rule.is_inside(winding) {
+                continue;
+            }
+
+            let (left, right) = (pair[0], pair[1]);
+            let base = positions.len() as u32;
+            positions.extend([
+                Vec2::new(left.x_at(top), top),
+                Vec2::new(right.x_at(top), top),
+                Vec2::new(right.x_at(bottom), bottom),
+                Vec2::new(left.x_at(bottom), bottom),
+            ]);
+            indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+    }
+
+    (positions, indices)
+}
+
+// ---------------< PRIVATE >---------------
+
+/// A non horizontal edge, stored from its lower to its upper end point.
+struct Edge {
+    min:     Vec2,
+    max:     Vec2,
+    winding: i32,
+}
+
+impl Edge {
+    fn new(from: Vec2, to: Vec2) -> Option<Self> {
+        if (from.y - to.y).abs() <= EPSILON {
+            return None;
+        }
+
+        Some(if from.y < to.y {
+            Self { min: from, max: to, winding: 1 }
+        } else {
+            Self { min: to, max: from, winding: -1 }
+        })
+    }
+
+    fn x_at(&self, y: f32) -> f32 {
+        let t = ((y - self.min.y) / (self.max.y - self.min.y)).clamp(0.0, 1.0);
+        self.min.x + (self.max.x - self.min.x) * t
+    }
+
+    fn intersection_y(&self, other: &Edge) -> Option<f32> {
+        let top = self.min.y.max(other.min.y);
+        let bottom = self.max.y.min(other.max.y);
+        if top >= bottom {
+            return None;
+        }
+
+        // Difference in x at both ends of the shared y range, a sign change means the edges cross
+        let start = self.x_at(top) - other.x_at(top);
+        let end = self.x_at(bottom) - other.x_at(bottom);
+        if start * end >= 0.0 {
+            return None;
+        }
+
+        Some(top + (bottom - top) * start / (start - end))
+    }
+}
+
+fn slab_breaks(edges: &[Edge]) -> Vec<f32> {
+    let mut breaks: Vec<f32> = edges.iter().flat_map(|edge| [edge.min.y, edge.max.y]).collect();
+
+    for (i, edge) in edges.iter().enumerate() {
+        breaks.extend(edges[i + 1..].iter().filter_map(|other| edge.intersection_y(other)));
+    }
+
+    breaks.sort_by(f32::total_cmp);
+    breaks.dedup_by(|a, b| (*a - *b).abs() <= EPSILON);
+    breaks
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, TAU};
+
+    use super::*;
+
+    fn area(positions: &[Vec2], indices: &[u32]) -> f32 {
+        indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| positions[triangle[i] as usize]);
+                (b - a).perp_dot(c - a).abs() * 0.5
+            })
+            .sum()
+    }
+
+    fn fill_area(contours: &[Vec<Vec2>], rule: FillRule) -> f32 {
+        let (positions, indices) = fill(contours, rule);
+        area(&positions, &indices)
+    }
+
+    fn square(min: f32, max: f32) -> Vec<Vec2> {
+        vec![Vec2::new(min, min), Vec2::new(max, min), Vec2::new(max, max), Vec2::new(min, max)]
+    }
+
+    fn assert_area(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3, "area {actual} != {expected}");
+    }
+
+    #[test]
+    fn pentagram() {
+        // Every second vertex of a pentagon, the contour crosses itself five times
+        let star: Vec<Vec2> = (0..5).map(|i| Vec2::from_angle(FRAC_PI_2 + i as f32 * 2.0 * TAU / 5.0)).collect();
+
+        let contours = [star];
+        assert_area(fill_area(&contours, FillRule::NonZero), 1.1226);
+        assert_area(fill_area(&contours, FillRule::EvenOdd), 0.7757);
+    }
+
+    #[test]
+    fn holes() {
+        let outer = square(0.0, 4.0);
+        let hole = square(1.0, 3.0);
+        let reversed: Vec<Vec2> = hole.iter().rev().copied().collect();
+
+        assert_area(fill_area(&[outer.clone(), reversed.clone()], FillRule::NonZero), 12.0);
+        assert_area(fill_area(&[outer.clone(), reversed], FillRule::EvenOdd), 12.0);
+        assert_area(fill_area(&[outer.clone(), hole.clone()], FillRule::NonZero), 16.0);
+        assert_area(fill_area(&[outer, hole], FillRule::EvenOdd), 12.0);
+    }
+
+    #[test]
+    fn overlapping_contours() {
+        let a = square(0.0, 2.0);
+        let b = square(1.0, 3.0);
+
+        assert_area(fill_area(&[a.clone(), b.clone()], FillRule::NonZero), 7.0);
+        assert_area(fill_area(&[a, b], FillRule::EvenOdd), 6.0);
+    }
+
+    #[test]
+    fn degenerate_contours() {
+        assert!(fill(&[], FillRule::NonZero).1.is_empty());
+        assert!(fill(&[vec![Vec2::ZERO, Vec2::ONE]], FillRule::NonZero).1.is_empty());
+        assert!(fill(&[vec![Vec2::ZERO, Vec2::X, Vec2::X * 2.0]], FillRule::NonZero).1.is_empty());
+    }
+
+    #[test]
+    fn fill_rules() {
+        assert!(!FillRule::NonZero.is_inside(0));
+        assert!(FillRule::NonZero.is_inside(2));
+        assert!(FillRule::NonZero.is_inside(-1));
+        assert!(!FillRule::EvenOdd.is_inside(2));
+        assert!(FillRule::EvenOdd.is_inside(-1));
+        assert!(FillRule::EvenOdd.is_inside(3));
+    }
+}
@@ -0,0 +1,181 @@
+use paper_math::Vec2;
+
+use crate::PathCommand;
+
+/// Upper bound for the number of segments a single curve is flattened into.
+const MAX_SEGMENTS: f32 = 1024.0;
+
+/// A flattened subpath.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Polyline {
+    pub points: Vec<Vec2>,
+    pub closed: bool,
+}
+
+/// Flattens path commands into polylines, splitting curves into as few straight segments as keep
+/// them within `tolerance` of the curve.
+pub(crate) fn flatten(commands: &[PathCommand], tolerance: f32) -> Vec<Polyline> {
+    let tolerance = tolerance.max(f32::EPSILON);
+    let mut polylines = Vec::new();
+    let mut current = Polyline::default();
+    let mut start = Vec2::ZERO;
+    let mut position = Vec2::ZERO;
+
+    let finish = |polylines: &mut Vec<Polyline>, polyline: Polyline| {
+        if polyline.points.len() > 1 {
+            polylines.push(polyline);
+        }
+    };
+
+    for command in commands {
+        if current.points.is_empty() && !matches!(command, PathCommand::MoveTo(_) | PathCommand::Close) {
+            current.points.push(position);
+            start = position;
+        }
+
+        match *command {
+            PathCommand::MoveTo(to) => {
+                finish(&mut polylines, std::mem::take(&mut current));
+                current.points.push(to);
+                start = to;
+                position = to;
+            }
+            PathCommand::LineTo(to) => {
+                current.points.push(to);
+                position = to;
+            }
+            PathCommand::QuadraticTo { ctrl, to } => {
+                let deviation = (position - 2.0 * ctrl + to).length();
+                let segments = segment_count(0.25 * deviation, tolerance);
+                current.points.extend((1..=segments).map(|i| {
+                    let t = i as f32 / segments as f32;
+                    let mt = 1.0 - t;
+                    position * (mt * mt) + ctrl * (2.0 * mt * t) + to * (t * t)
+                }));
+                position = to;
+            }
+            PathCommand::CubicTo { ctrl1, ctrl2, to } => {
+                let deviation = (position - 2.0 * ctrl1 + ctrl2).length().max((ctrl1 - 2.0 * ctrl2 + to).length());
+                let segments = segment_count(0.75 * deviation, tolerance);
+                current.points.extend((1..=segments).map(|i| {
+                    let t = i as f32 / segments as f32;
+                    let mt = 1.0 - t;
+                    position * (mt * mt * mt)
+                        + ctrl1 * (3.0 * mt * mt * t)
+                        + ctrl2 * (3.0 * mt * t * t)
+                        + to * (t * t * t)
+                }));
+                position = to;
+            }
+            PathCommand::Close => {
+                current.closed = true;
+                finish(&mut polylines, std::mem::take(&mut current));
+                position = start;
+            }
+        }
+    }
+    finish(&mut polylines, current);
+
+    polylines
+}
+
+// ---------------< PRIVATE >---------------
+
+/// Wang's formula, the number of uniform segments that keep a Bézier curve within `tolerance`.
+fn segment_count(scaled_deviation: f32, tolerance: f32) -> u32 {
+    (scaled_deviation / tolerance).sqrt().ceil().clamp(1.0, MAX_SEGMENTS) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance_to_polyline(point: Vec2, points: &[Vec2]) -> f32 {
+        points
+            .windows(2)
+            .map(|segment| {
+                let (a, b) = (segment[0], segment[1]);
+                let t = ((point - a).dot(b - a) / (b - a).length_squared()).clamp(0.0, 1.0);
+                point.distance(a + (b - a) * t)
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    fn cubic(from: Vec2, ctrl1: Vec2, ctrl2: Vec2, to: Vec2, t: f32) -> Vec2 {
+        let mt = 1.0 - t;
+        from * (mt * mt * mt) + ctrl1 * (3.0 * mt * mt * t) + ctrl2 * (3.0 * mt * t * t) + to * (t * t * t)
+    }
+
+    #[test]
+    fn curves_stay_within_tolerance() {
+        let (from, ctrl1, ctrl2, to) =
+            (Vec2::ZERO, Vec2::new(0.0, 300.0), Vec2::new(200.0, -150.0), Vec2::new(250.0, 100.0));
+        let commands = [PathCommand::MoveTo(from), PathCommand::CubicTo { ctrl1, ctrl2, to }];
+        let mut previous_count = 0;
+
+        for tolerance in [10.0, 1.0, 0.25, 0.01] {
+            let polylines = flatten(&commands, tolerance);
+            let points = &polylines[0].points;
+
+            assert_eq!((points[0], *points.last().unwrap()), (from, to));
+            for i in 0..=1000 {
+                let point = cubic(from, ctrl1, ctrl2, to, i as f32 / 1000.0);
+                let distance = distance_to_polyline(point, points);
+                assert!(distance <= tolerance * 1.01 + 1e-3, "{distance} > {tolerance}");
+            }
+
+            // Tighter tolerances need more segments, but not needlessly many
+            assert!(points.len() > previous_count);
+            previous_count = points.len();
+        }
+        assert!(previous_count < 300, "{previous_count} points");
+    }
+
+    #[test]
+    fn quadratic_within_tolerance() {
+        let (from, ctrl, to) = (Vec2::ZERO, Vec2::new(50.0, 100.0), Vec2::new(100.0, 0.0));
+        let commands = [PathCommand::QuadraticTo { ctrl, to }];
+        let points = &flatten(&commands, 0.1)[0].points;
+
+        for i in 0..=1000 {
+            let t = i as f32 / 1000.0;
+            let point = from * (1.0 - t) * (1.0 - t) + ctrl * (2.0 * (1.0 - t) * t) + to * (t * t);
+            assert!(distance_to_polyline(point, points) <= 0.101);
+        }
+    }
+
+    #[test]
+    fn segment_count_is_bounded() {
+        let commands = [PathCommand::QuadraticTo { ctrl: Vec2::new(1e6, 1e6), to: Vec2::new(2e6, 0.0) }];
+        assert_eq!(flatten(&commands, 0.0)[0].points.len(), MAX_SEGMENTS as usize + 1);
+
+        // Straight curves are a single segment
+        let commands = [PathCommand::CubicTo { ctrl1: Vec2::X, ctrl2: Vec2::X * 2.0, to: Vec2::X * 3.0 }];
+        assert_eq!(flatten(&commands, 0.25)[0].points.len(), 2);
+    }
+
+    #[test]
+    fn subpaths() {
+        let commands = [
+            PathCommand::LineTo(Vec2::X),
+            PathCommand::LineTo(Vec2::ONE),
+            PathCommand::Close,
+            PathCommand::LineTo(Vec2::Y),
+            PathCommand::MoveTo(Vec2::splat(5.0)),
+            PathCommand::MoveTo(Vec2::splat(6.0)),
+            PathCommand::LineTo(Vec2::splat(7.0)),
+        ];
+        let polylines = flatten(&commands, 0.25);
+
+        // Drawing starts at the origin, after a close at the start of the closed subpath, and lone
+        // move commands draw nothing
+        assert_eq!(
+            polylines,
+            [
+                Polyline { points: vec![Vec2::ZERO, Vec2::X, Vec2::ONE], closed: true },
+                Polyline { points: vec![Vec2::ZERO, Vec2::Y], closed: false },
+                Polyline { points: vec![Vec2::splat(6.0), Vec2::splat(7.0)], closed: false },
+            ]
+        );
+    }
+}
@@ -0,0 +1,7 @@
+mod fill;
+mod flatten;
+mod path;
+
+pub use fill::*;
+pub use flatten::*;
+pub use path::*;
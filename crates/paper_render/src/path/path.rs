@@ -0,0 +1,326 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use paper_math::Vec2;
+
+use crate::{FillRule, Mesh, Polyline, Stroke, Topology, positions_from_vec2s};
+
+/// Default maximum distance between a curve and its flattened polyline.
+pub const DEFAULT_TOLERANCE: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo(Vec2),
+    LineTo(Vec2),
+    QuadraticTo { ctrl: Vec2, to: Vec2 },
+    CubicTo { ctrl1: Vec2, ctrl2: Vec2, to: Vec2 },
+    Close,
+}
+
+/// A vector path made of one or more subpaths of lines and Bézier curves.
+///
+/// Drawing without a preceding [`move_to`](Path::move_to) starts at the origin, or at the start of
+/// the previous subpath after [`close`](Path::close).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    commands:  Vec<PathCommand>,
+    tolerance: f32,
+    start:     Vec2,
+    current:   Vec2,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum distance between curves and the polylines they are flattened into.
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn tolerance(&self) -> f32 {
+        self.tolerance
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    /// The end point of the last command.
+    pub fn current(&self) -> Vec2 {
+        self.current
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn move_to(&mut self, to: Vec2) -> &mut Self {
+        self.start = to;
+        self.current = to;
+        self.commands.push(PathCommand::MoveTo(to));
+        self
+    }
+
+    pub fn line_to(&mut self, to: Vec2) -> &mut Self {
+        self.current = to;
+        self.commands.push(PathCommand::LineTo(to));
+        self
+    }
+
+    pub fn quadratic_to(&mut self, ctrl: Vec2, to: Vec2) -> &mut Self {
+        self.current = to;
+        self.commands.push(PathCommand::QuadraticTo { ctrl, to });
+        self
+    }
+
+    pub fn cubic_to(&mut self, ctrl1: Vec2, ctrl2: Vec2, to: Vec2) -> &mut Self {
+        self.current = to;
+        self.commands.push(PathCommand::CubicTo { ctrl1, ctrl2, to });
+        self
+    }
+
+    /// Elliptical arc to `to`, with the same parameters as the SVG `A` command. `rotation` is the
+    /// rotation of the ellipse in radians, the flags pick one of the four possible arcs.
+    ///
+    /// The arc is stored as cubic Bézier curves. Radii that are too small to reach `to` are scaled
+    /// up, a zero radius draws a straight line.
+    pub fn arc_to(&mut self, radii: Vec2, rotation: f32, large_arc: bool, sweep: bool, to: Vec2) -> &mut Self {
+        let from = self.current;
+        if from == to {
+            return self;
+        }
+
+        let mut radii = radii.abs();
+        if radii.x <= f32::EPSILON || radii.y <= f32::EPSILON {
+            return self.line_to(to);
+        }
+
+        // Conversion from endpoint to center parameterization, see the SVG implementation notes
+        let axis = Vec2::from_angle(rotation);
+        let local = Vec2::from_angle(-rotation).rotate((from - to) * 0.5);
+
+        let lambda = (local / radii).length_squared();
+        if lambda > 1.0 {
+            radii *= lambda.sqrt();
+        }
+
+        let (rx2, ry2) = (radii.x * radii.x, radii.y * radii.y);
+        let (x2, y2) = (local.x * local.x, local.y * local.y);
+        let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+        let coefficient = sign * ((rx2 * ry2 - rx2 * y2 - ry2 * x2) / (rx2 * y2 + ry2 * x2)).max(0.0).sqrt();
+        let center_local = coefficient * Vec2::new(radii.x * local.y / radii.y, -radii.y * local.x / radii.x);
+        let center = axis.rotate(center_local) + (from + to) * 0.5;
+
+        let start = (local - center_local) / radii;
+        let end = (-local - center_local) / radii;
+        let start_angle = start.to_angle();
+        let mut sweep_angle = start.angle_to(end);
+        if !sweep && sweep_angle > 0.0 {
+            sweep_angle -= TAU;
+        } else if sweep && sweep_angle < 0.0 {
+            sweep_angle += TAU;
+        }
+
+        let point = |angle: f32| center + axis.rotate(Vec2::from_angle(angle) * radii);
+        let tangent = |angle: f32| axis.rotate(Vec2::from_angle(angle).perp() * radii);
+
+        // Cubics approximate arcs of up to a quarter turn closely
+        let pieces = (sweep_angle.abs() / FRAC_PI_2).ceil().max(1.0) as u32;
+        let step = sweep_angle / pieces as f32;
+        let handle = 4.0 / 3.0 * (step / 4.0).tan();
+
+        for i in 0..pieces {
+            let a0 = start_angle + step * i as f32;
+            let a1 = a0 + step;
+            let end = if i + 1 == pieces { to } else { point(a1) };
+            self.cubic_to(point(a0) + tangent(a0) * handle, point(a1) - tangent(a1) * handle, end);
+        }
+        self
+    }
+
+    /// Closes the current subpath with a straight line back to its start.
+    pub fn close(&mut self) -> &mut Self {
+        self.current = self.start;
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    /// The subpaths flattened into polylines, see [`with_tolerance`](Path::with_tolerance).
+    pub fn flatten(&self) -> Vec<Polyline> {
+        crate::flatten(&self.commands, self.tolerance)
+    }
+
+    /// Triangles covering the inside of the path. Every subpath is treated as closed.
+    pub fn tessellate_fill(&self, rule: FillRule) -> (Vec<Vec2>, Vec<u32>) {
+        let contours: Vec<Vec<Vec2>> = self.flatten().into_iter().map(|polyline| polyline.points).collect();
+        crate::fill(&contours, rule)
+    }
+
+    pub fn tessellate_stroke(&self, stroke: &Stroke) -> (Vec<Vec2>, Vec<u32>) {
+        let (mut positions, mut indices) = (Vec::new(), Vec::new());
+        for polyline in self.flatten() {
+            let (polyline_positions, polyline_indices) = stroke.tessellate(&polyline.points, polyline.closed);
+            let base = positions.len() as u32;
+            positions.extend(polyline_positions);
+            indices.extend(polyline_indices.into_iter().map(|i| base + i));
+        }
+        (positions, indices)
+    }
+
+    pub fn fill_mesh(&self, rule: FillRule) -> Mesh {
+        let (positions, indices) = self.tessellate_fill(rule);
+        triangle_mesh(&positions, indices)
+    }
+
+    pub fn stroke_mesh(&self, stroke: &Stroke) -> Mesh {
+        let (positions, indices) = self.tessellate_stroke(stroke);
+        triangle_mesh(&positions, indices)
+    }
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Self { commands: Vec::new(), tolerance: DEFAULT_TOLERANCE, start: Vec2::ZERO, current: Vec2::ZERO }
+    }
+}
+
+// ---------------< PRIVATE >---------------
+
+fn triangle_mesh(positions: &[Vec2], indices: Vec<u32>) -> Mesh {
+    Mesh::new()
+        .with_attribute(crate::AttributeType::Position, positions_from_vec2s(positions))
+        .with_indices(indices)
+        .with_topology(Topology::Triangles)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn arc(from: Vec2, radii: Vec2, rotation: f32, large_arc: bool, sweep: bool, to: Vec2) -> Path {
+        let mut path = Path::new().with_tolerance(0.001);
+        path.move_to(from).arc_to(radii, rotation, large_arc, sweep, to);
+        path
+    }
+
+    fn points(path: &Path) -> Vec<Vec2> {
+        path.flatten().into_iter().flat_map(|polyline| polyline.points).collect()
+    }
+
+    fn cubic_count(path: &Path) -> usize {
+        path.commands().iter().filter(|command| matches!(command, PathCommand::CubicTo { .. })).count()
+    }
+
+    fn assert_on_circle(points: &[Vec2], center: Vec2, radius: f32) {
+        for point in points {
+            assert!((point.distance(center) - radius).abs() < 2e-3, "{point} is not on the circle around {center}");
+        }
+    }
+
+    fn assert_passes(points: &[Vec2], expected: Vec2) {
+        let closest = points
+            .windows(2)
+            .map(|segment| {
+                let (a, b) = (segment[0], segment[1]);
+                let t = ((expected - a).dot(b - a) / (b - a).length_squared()).clamp(0.0, 1.0);
+                expected.distance(a + (b - a) * t)
+            })
+            .fold(f32::INFINITY, f32::min);
+        assert!(closest < 0.01, "the arc does not pass through {expected}");
+    }
+
+    #[test]
+    fn arc_flags() {
+        let (from, to) = (Vec2::X, Vec2::Y);
+        let diagonal = Vec2::splat(std::f32::consts::FRAC_1_SQRT_2);
+
+        // (large_arc, sweep, center, a point in the middle of the arc, cubic pieces)
+        let cases = [
+            (false, true, Vec2::ZERO, diagonal, 1),
+            (false, false, Vec2::ONE, Vec2::ONE - diagonal, 1),
+            (true, true, Vec2::ONE, Vec2::ONE + diagonal, 3),
+            (true, false, Vec2::ZERO, -diagonal, 3),
+        ];
+
+        for (large_arc, sweep, center, middle, pieces) in cases {
+            let path = arc(from, Vec2::ONE, 0.0, large_arc, sweep, to);
+            let points = points(&path);
+
+            assert_eq!(path.current(), to);
+            assert_eq!(*points.last().unwrap(), to);
+            assert_eq!(cubic_count(&path), pieces, "large_arc {large_arc}, sweep {sweep}");
+            assert_on_circle(&points, center, 1.0);
+            assert_passes(&points, middle);
+        }
+    }
+
+    #[test]
+    fn arc_radii_too_small_are_scaled_up() {
+        for (large_arc, sweep) in [(false, false), (false, true), (true, false), (true, true)] {
+            let path = arc(Vec2::ZERO, Vec2::new(1.0, 0.5), 0.0, large_arc, sweep, Vec2::new(10.0, 0.0));
+            let points = points(&path);
+
+            // The smallest ellipse of the same proportions reaching the end point is a half ellipse
+            let center = Vec2::new(5.0, 0.0);
+            for point in &points {
+                let local = (*point - center) / Vec2::new(5.0, 2.5);
+                assert!((local.length() - 1.0).abs() < 1e-3, "{point} is not on the ellipse");
+            }
+            assert_passes(&points, Vec2::new(5.0, if sweep { -2.5 } else { 2.5 }));
+            assert_eq!(path.current(), Vec2::new(10.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn rotated_arc() {
+        let path = arc(Vec2::new(0.0, -2.0), Vec2::new(2.0, 1.0), FRAC_PI_2, false, true, Vec2::new(0.0, 2.0));
+        let points = points(&path);
+
+        for point in &points {
+            assert!((point.x * point.x + point.y * point.y / 4.0 - 1.0).abs() < 2e-3, "{point}");
+        }
+        assert_passes(&points, Vec2::X);
+    }
+
+    #[test]
+    fn degenerate_arcs() {
+        let path = arc(Vec2::ZERO, Vec2::new(0.0, 5.0), 0.0, false, true, Vec2::ONE);
+        assert_eq!(path.commands(), [PathCommand::MoveTo(Vec2::ZERO), PathCommand::LineTo(Vec2::ONE)]);
+
+        let path = arc(Vec2::ZERO, Vec2::ZERO, 0.0, true, false, Vec2::ONE);
+        assert_eq!(path.commands(), [PathCommand::MoveTo(Vec2::ZERO), PathCommand::LineTo(Vec2::ONE)]);
+
+        let path = arc(Vec2::ONE, Vec2::ONE, 0.0, true, true, Vec2::ONE);
+        assert_eq!(path.commands(), [PathCommand::MoveTo(Vec2::ONE)]);
+
+        // Negative radii are treated as positive
+        let negative = arc(Vec2::X, -Vec2::ONE, 0.0, false, true, Vec2::Y);
+        let positive = arc(Vec2::X, Vec2::ONE, 0.0, false, true, Vec2::Y);
+        assert_eq!(negative.commands(), positive.commands());
+    }
+
+    #[test]
+    fn fill_and_stroke_meshes() {
+        let mut path = Path::new();
+        path.move_to(Vec2::ZERO).line_to(Vec2::new(2.0, 0.0)).line_to(Vec2::new(2.0, 2.0)).close();
+
+        let (positions, indices) = path.tessellate_fill(FillRule::NonZero);
+        let area: f32 = indices
+            .chunks_exact(3)
+            .map(|t| {
+                let [a, b, c] = [0, 1, 2].map(|i| positions[t[i] as usize]);
+                (b - a).perp_dot(c - a).abs() * 0.5
+            })
+            .sum();
+        assert!((area - 2.0).abs() < 1e-4);
+
+        let (positions, indices) = path.tessellate_stroke(&Stroke::new(0.5));
+        assert!(!indices.is_empty());
+        assert!(indices.iter().all(|i| (*i as usize) < positions.len()));
+        assert_eq!(path.fill_mesh(FillRule::EvenOdd).topology(), Topology::Triangles);
+    }
+}
@@ -0,0 +1,400 @@
+use std::{fmt, io, path::Path as FilePath, str::FromStr};
+
+use hashbrown::HashMap;
+use log::{debug, warn};
+use paper_math::{Affine2, Rect, Vec2};
+
+use super::{
+    path_data::{parse_length, parse_numbers, parse_path_data, parse_transform},
+    style::{Gradient, Paint, Style},
+    xml::{self, Element},
+};
+use crate::{AttributeType, DEFAULT_TOLERANCE, Mesh, Path, Topology, VertexFormat, positions_from_vec2s};
+
+/// Vector graphics loaded from an SVG document, tessellated into colored triangles.
+///
+/// Paths, basic shapes, groups and transforms with solid or gradient fills and strokes are supported.
+/// Gradients are evaluated at the vertices. Text, images, clipping, masks, filters and `use`
+/// elements are skipped.
+///
+/// The y axis is flipped to point up and the origin is moved to the center of the `viewBox`, or of
+/// the `width` and `height` if there is none. Opacity is kept in the alpha channel of the colors, which
+/// [`DefaultMaterial`](crate::DefaultMaterial) blends with whatever was drawn before.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Svg {
+    size:   Vec2,
+    shapes: Vec<SvgShape>,
+}
+
+impl Svg {
+    /// Parses a document, flattening curves to within `tolerance` of the output coordinates.
+    pub fn parse(source: &str, tolerance: f32) -> Result<Self, ParseSvgError> {
+        let root = xml::parse(source)?;
+        if root.name != "svg" {
+            return Err(ParseSvgError::new(format!("expected root element 'svg' but found '{}'", root.name)));
+        }
+
+        let view_box = root.attribute("viewBox").and_then(parse_numbers);
+        let (origin, size) = match view_box.as_deref() {
+            Some(&[x, y, width, height]) => (Vec2::new(x, y), Vec2::new(width, height)),
+            _ => {
+                let length = |name| root.attribute(name).and_then(parse_length).unwrap_or(0.0);
+                (Vec2::ZERO, Vec2::new(length("width"), length("height")))
+            }
+        };
+
+        let view = Affine2::from_scale(Vec2::new(1.0, -1.0)) * Affine2::from_translation(-(origin + size * 0.5));
+
+        let elements = root
+            .descendants()
+            .into_iter()
+            .filter_map(|element| element.attribute("id").map(|id| (id, element)))
+            .collect();
+        let mut builder = Builder { elements, tolerance, shapes: Vec::new() };
+        builder.visit(&root, &Style::default(), view)?;
+
+        Ok(Self { size, shapes: builder.shapes })
+    }
+
+    pub fn load(path: impl AsRef<FilePath>) -> io::Result<Self> {
+        std::fs::read_to_string(path)?.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Size of the `viewBox`, or the `width` and `height` of the document.
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    /// One shape per drawn element, in document order.
+    pub fn shapes(&self) -> &[SvgShape] {
+        &self.shapes
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::from_points(self.shapes.iter().flat_map(|shape| shape.positions.iter().copied()))
+    }
+
+    /// All shapes combined into a single mesh with [`AttributeType::Color`] vertex colors.
+    pub fn mesh(&self) -> Mesh {
+        let mut combined = SvgShape::default();
+        for shape in &self.shapes {
+            combined.append(shape.positions.clone(), shape.indices.clone(), shape.colors.clone());
+        }
+        combined.mesh()
+    }
+}
+
+impl FromStr for Svg {
+    type Err = ParseSvgError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, DEFAULT_TOLERANCE)
+    }
+}
+
+/// The fill and stroke of a single SVG element.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SvgShape {
+    id:        Option<String>,
+    positions: Vec<Vec2>,
+    colors:    Vec<[f32; 4]>,
+    indices:   Vec<u32>,
+}
+
+impl SvgShape {
+    /// The `id` attribute of the element.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::from_points(self.positions.iter().copied())
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        Mesh::new()
+            .with_attribute(AttributeType::Position, positions_from_vec2s(&self.positions))
+            .with_attribute(AttributeType::Color, self.colors.iter().flatten().copied().collect())
+            .with_attribute_format(&AttributeType::Color, VertexFormat::U8Norm)
+            .with_indices(self.indices.clone())
+            .with_topology(Topology::Triangles)
+    }
+
+    // ---------------< PRIVATE >---------------
+
+    fn append(&mut self, positions: Vec<Vec2>, indices: Vec<u32>, colors: Vec<[f32; 4]>) {
+        let base = self.positions.len() as u32;
+        self.positions.extend(positions);
+        self.colors.extend(colors);
+        self.indices.extend(indices.into_iter().map(|i| base + i));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSvgError {
+    message: String,
+}
+
+impl ParseSvgError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl fmt::Display for ParseSvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid svg: {}", self.message)
+    }
+}
+
+impl std::error::Error for ParseSvgError {}
+
+// ---------------< PRIVATE >---------------
+
+struct Builder<'a> {
+    elements:  HashMap<&'a str, &'a Element>,
+    tolerance: f32,
+    shapes:    Vec<SvgShape>,
+}
+
+impl Builder<'_> {
+    fn visit(
+        &mut self,
+        element: &Element,
+        parent_style: &Style,
+        parent_transform: Affine2,
+    ) -> Result<(), ParseSvgError> {
+        let style = parent_style.inherit(element);
+        if !style.display {
+            return Ok(());
+        }
+
+        let transform = match element.attribute("transform") {
+            Some(value) => parent_transform * parse_transform(value)?,
+            None => parent_transform,
+        };
+
+        match element.name.as_str() {
+            "svg" | "g" | "a" | "switch" => {
+                for child in &element.children {
+                    self.visit(child, &style, transform)?;
+                }
+            }
+            "path" | "rect" | "circle" | "ellipse" | "line" | "polyline" | "polygon" => {
+                if style.visible
+                    && let Some(path) = shape_path(element)?
+                {
+                    self.add_shape(element, path, &style, transform);
+                }
+            }
+            // Definitions are only drawn when referenced
+            "defs" | "linearGradient" | "radialGradient" | "clipPath" | "mask" | "pattern" | "symbol" | "marker"
+            | "style" | "title" | "desc" | "metadata" => {}
+            name => debug!("Skipping unsupported SVG element '{name}'"),
+        }
+
+        Ok(())
+    }
+
+    fn add_shape(&mut self, element: &Element, path: Path, style: &Style, transform: Affine2) {
+        // Curves are flattened before the transform is applied, so the tolerance is scaled with it
+        let scale = transform.matrix2.determinant().abs().sqrt().max(f32::EPSILON);
+        let path = path.with_tolerance(self.tolerance / scale);
+        let bounds = Rect::from_points(path.flatten().into_iter().flat_map(|polyline| polyline.points));
+
+        let mut shape = SvgShape { id: element.attribute("id").map(str::to_string), ..Default::default() };
+
+        if style.fill != Paint::None {
+            let (positions, indices) = path.tessellate_fill(style.fill_rule);
+            if let Some(colors) = self.colors(&style.fill, style.fill_opacity * style.opacity, &positions, &bounds) {
+                shape.append(positions, indices, colors);
+            }
+        }
+
+        if style.stroke != Paint::None && style.stroke_width > 0.0 {
+            let (positions, indices) = path.tessellate_stroke(&style.stroke());
+            if let Some(colors) = self.colors(&style.stroke, style.stroke_opacity * style.opacity, &positions, &bounds)
+            {
+                shape.append(positions, indices, colors);
+            }
+        }
+
+        if shape.indices.is_empty() {
+            return;
+        }
+        for position in &mut shape.positions {
+            *position = transform.transform_point2(*position);
+        }
+        self.shapes.push(shape);
+    }
+
+    fn colors(&self, paint: &Paint, opacity: f32, positions: &[Vec2], bounds: &Rect) -> Option<Vec<[f32; 4]>> {
+        let with_opacity = |mut color: [f32; 4]| {
+            color[3] *= opacity;
+            color
+        };
+
+        match paint {
+            Paint::None => None,
+            Paint::Color(color) => Some(vec![with_opacity(*color); positions.len()]),
+            Paint::Gradient(id) => {
+                let Some(gradient) = Gradient::resolve(id, &self.elements) else {
+                    warn!("SVG paint '{id}' not found, the element is not painted");
+                    return None;
+                };
+                Some(positions.iter().map(|position| with_opacity(gradient.color_at(*position, bounds))).collect())
+            }
+        }
+    }
+}
+
+/// The outline of a shape element, `None` if it has nothing to draw.
+fn shape_path(element: &Element) -> Result<Option<Path>, ParseSvgError> {
+    let number = |name: &str| element.attribute(name).and_then(parse_length);
+    let mut path = Path::new();
+
+    match element.name.as_str() {
+        "path" => parse_path_data(element.attribute("d").unwrap_or_default(), &mut path)?,
+        "rect" => {
+            let (x, y) = (number("x").unwrap_or(0.0), number("y").unwrap_or(0.0));
+            let size = Vec2::new(number("width").unwrap_or(0.0), number("height").unwrap_or(0.0));
+            if size.x <= 0.0 || size.y <= 0.0 {
+                return Ok(None);
+            }
+
+            // A missing corner radius defaults to the other one
+            let rx = number("rx").or(number("ry")).unwrap_or(0.0);
+            let ry = number("ry").or(number("rx")).unwrap_or(0.0);
+            let radii = Vec2::new(rx, ry).clamp(Vec2::ZERO, size * 0.5);
+            let (min, max) = (Vec2::new(x, y), Vec2::new(x, y) + size);
+
+            if radii.min_element() <= 0.0 {
+                path.move_to(min)
+                    .line_to(Vec2::new(max.x, min.y))
+                    .line_to(max)
+                    .line_to(Vec2::new(min.x, max.y))
+                    .close();
+            } else {
+                path.move_to(Vec2::new(min.x + radii.x, min.y))
+                    .line_to(Vec2::new(max.x - radii.x, min.y))
+                    .arc_to(radii, 0.0, false, true, Vec2::new(max.x, min.y + radii.y))
+                    .line_to(Vec2::new(max.x, max.y - radii.y))
+                    .arc_to(radii, 0.0, false, true, Vec2::new(max.x - radii.x, max.y))
+                    .line_to(Vec2::new(min.x + radii.x, max.y))
+                    .arc_to(radii, 0.0, false, true, Vec2::new(min.x, max.y - radii.y))
+                    .line_to(Vec2::new(min.x, min.y + radii.y))
+                    .arc_to(radii, 0.0, false, true, Vec2::new(min.x + radii.x, min.y))
+                    .close();
+            }
+        }
+        "circle" | "ellipse" => {
+            let center = Vec2::new(number("cx").unwrap_or(0.0), number("cy").unwrap_or(0.0));
+            let radii = match element.name.as_str() {
+                "circle" => Vec2::splat(number("r").unwrap_or(0.0)),
+                _ => Vec2::new(number("rx").unwrap_or(0.0), number("ry").unwrap_or(0.0)),
+            };
+            if radii.min_element() <= 0.0 {
+                return Ok(None);
+            }
+
+            path.move_to(center + Vec2::new(radii.x, 0.0))
+                .arc_to(radii, 0.0, false, true, center - Vec2::new(radii.x, 0.0))
+                .arc_to(radii, 0.0, false, true, center + Vec2::new(radii.x, 0.0))
+                .close();
+        }
+        "line" => {
+            path.move_to(Vec2::new(number("x1").unwrap_or(0.0), number("y1").unwrap_or(0.0)))
+                .line_to(Vec2::new(number("x2").unwrap_or(0.0), number("y2").unwrap_or(0.0)));
+        }
+        _ => {
+            let points = element
+                .attribute("points")
+                .and_then(parse_numbers)
+                .ok_or_else(|| ParseSvgError::new(format!("invalid points of '{}'", element.name)))?;
+            let mut points = points.chunks_exact(2).map(|pair| Vec2::new(pair[0], pair[1]));
+
+            let Some(first) = points.next() else {
+                return Ok(None);
+            };
+            path.move_to(first);
+            for point in points {
+                path.line_to(point);
+            }
+            if element.name == "polygon" {
+                path.close();
+            }
+        }
+    }
+
+    Ok((!path.is_empty()).then_some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_input_is_an_error() {
+        for source in [
+            "<svg viewBox=\"0 0 10 10\"><path d=\"M0\u{a0}0 L 5 5\"/></svg>",
+            "<svg viewBox=\"0 0 10 10\"><path d=\"M0 0 L 5 5\u{3000}\"/></svg>",
+            "<svg viewBox=\"0 0 10 10\"><path d=\"M0 0 Lé 5 5\"/></svg>",
+            "<svg><polygon points=\"0,0 1,\u{2003}1\"/></svg>",
+            "<svg><g transform=\"rotate(45°)\"><rect width=\"1\" height=\"1\"/></g></svg>",
+            "<svg><rect width=\"1\" height=\"1\"></svg>",
+            "<svg>\u{feff}",
+            "<ｓｖｇ/>",
+            "<html/>",
+        ] {
+            assert!(source.parse::<Svg>().is_err(), "'{source}' should be rejected");
+        }
+    }
+
+    #[test]
+    fn opacity_is_kept_in_alpha() {
+        let svg: Svg = r##"<svg viewBox="0 0 10 10">
+            <rect width="10" height="10" fill="#00f" fill-opacity="0.5" stroke="#f00" stroke-opacity="0.5"/>
+            <g opacity="0.5"><rect width="5" height="5" fill="#0f0" fill-opacity="0.5"/></g>
+        </svg>"##
+            .parse()
+            .unwrap();
+
+        let alphas: Vec<Vec<f32>> =
+            svg.shapes().iter().map(|shape| shape.colors.iter().map(|color| color[3]).collect()).collect();
+        assert_eq!(alphas.len(), 2);
+        assert!(alphas[0].iter().all(|alpha| *alpha == 0.5));
+        assert!(alphas[1].iter().all(|alpha| *alpha == 0.25));
+    }
+
+    #[test]
+    fn shapes_are_centered_with_y_up() {
+        let svg: Svg = r##"<svg viewBox="0 0 100 50">
+            <rect id="box" x="0" y="0" width="10" height="10" fill="#f00"/>
+            <g display="none"><circle r="5"/></g>
+            <circle cx="50" cy="25" r="5" fill="none"/>
+            <line x1="0" y1="0" x2="100" y2="50" stroke="blue" stroke-width="2"/>
+        </svg>"##
+            .parse()
+            .unwrap();
+
+        assert_eq!(svg.size(), Vec2::new(100.0, 50.0));
+        assert_eq!(svg.shapes().len(), 2);
+
+        let rect = &svg.shapes()[0];
+        assert_eq!(rect.id(), Some("box"));
+        assert_eq!(rect.bounds(), Rect::new(Vec2::new(-50.0, 15.0), Vec2::new(-40.0, 25.0)));
+        assert!(rect.colors.iter().all(|color| *color == [1.0, 0.0, 0.0, 1.0]));
+
+        let line = &svg.shapes()[1];
+        assert!(line.colors.iter().all(|color| *color == [0.0, 0.0, 1.0, 1.0]));
+        assert!(svg.bounds().contains(Vec2::new(50.0, -25.0)));
+    }
+
+    #[test]
+    fn size_from_width_and_height() {
+        let svg: Svg = r#"<svg width="20px" height="10px"><rect width="20" height="10"/></svg>"#.parse().unwrap();
+
+        assert_eq!(svg.size(), Vec2::new(20.0, 10.0));
+        assert_eq!(svg.bounds(), Rect::new(Vec2::new(-10.0, -5.0), Vec2::new(10.0, 5.0)));
+    }
+}
@@ -0,0 +1,6 @@
+mod document;
+mod path_data;
+mod style;
+mod xml;
+
+pub use document::*;
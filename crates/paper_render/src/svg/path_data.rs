@@ -0,0 +1,349 @@
+use paper_math::{Affine2, Vec2};
+
+use crate::{ParseSvgError, Path};
+
+/// Builds a [`Path`] from SVG path data, e.g. `M 0 0 L 10 0 q 5 5 10 0 z`.
+pub(crate) fn parse_path_data(data: &str, path: &mut Path) -> Result<(), ParseSvgError> {
+    let error = || ParseSvgError::new(format!("invalid path data '{data}'"));
+    let mut numbers = Numbers::new(data);
+
+    let mut command = None;
+    // Reflected control points for the smooth curve commands
+    let mut last_cubic_ctrl: Option<Vec2> = None;
+    let mut last_quadratic_ctrl: Option<Vec2> = None;
+
+    loop {
+        numbers.skip_separators();
+        let Some(next) = numbers.peek() else {
+            return Ok(());
+        };
+
+        if next.is_ascii_alphabetic() {
+            numbers.advance(1);
+            command = Some(next);
+        } else if command.is_none() {
+            return Err(error());
+        }
+        let Some(current) = command else {
+            return Err(error());
+        };
+
+        let relative = current.is_ascii_lowercase();
+        let origin = if relative { path.current() } else { Vec2::ZERO };
+        let point = |numbers: &mut Numbers| -> Result<Vec2, ParseSvgError> {
+            Ok(origin + Vec2::new(numbers.number().ok_or_else(error)?, numbers.number().ok_or_else(error)?))
+        };
+
+        let (cubic_ctrl, quadratic_ctrl) = match current.to_ascii_uppercase() {
+            'M' => {
+                path.move_to(point(&mut numbers)?);
+                // Further coordinate pairs are implicit line commands
+                command = Some(if relative { 'l' } else { 'L' });
+                (None, None)
+            }
+            'L' => {
+                path.line_to(point(&mut numbers)?);
+                (None, None)
+            }
+            'H' => {
+                let x = numbers.number().ok_or_else(error)?;
+                path.line_to(Vec2::new(if relative { path.current().x + x } else { x }, path.current().y));
+                (None, None)
+            }
+            'V' => {
+                let y = numbers.number().ok_or_else(error)?;
+                path.line_to(Vec2::new(path.current().x, if relative { path.current().y + y } else { y }));
+                (None, None)
+            }
+            'C' => {
+                let (ctrl1, ctrl2, to) = (point(&mut numbers)?, point(&mut numbers)?, point(&mut numbers)?);
+                path.cubic_to(ctrl1, ctrl2, to);
+                (Some(ctrl2), None)
+            }
+            'S' => {
+                let ctrl1 = reflect(last_cubic_ctrl, path.current());
+                let (ctrl2, to) = (point(&mut numbers)?, point(&mut numbers)?);
+                path.cubic_to(ctrl1, ctrl2, to);
+                (Some(ctrl2), None)
+            }
+            'Q' => {
+                let (ctrl, to) = (point(&mut numbers)?, point(&mut numbers)?);
+                path.quadratic_to(ctrl, to);
+                (None, Some(ctrl))
+            }
+            'T' => {
+                let ctrl = reflect(last_quadratic_ctrl, path.current());
+                path.quadratic_to(ctrl, point(&mut numbers)?);
+                (None, Some(ctrl))
+            }
+            'A' => {
+                let radii = Vec2::new(numbers.number().ok_or_else(error)?, numbers.number().ok_or_else(error)?);
+                let rotation = numbers.number().ok_or_else(error)?.to_radians();
+                let large_arc = numbers.flag().ok_or_else(error)?;
+                let sweep = numbers.flag().ok_or_else(error)?;
+                path.arc_to(radii, rotation, large_arc, sweep, point(&mut numbers)?);
+                (None, None)
+            }
+            'Z' => {
+                path.close();
+                command = None;
+                (None, None)
+            }
+            _ => return Err(error()),
+        };
+
+        last_cubic_ctrl = cubic_ctrl;
+        last_quadratic_ctrl = quadratic_ctrl;
+    }
+}
+
+/// Parses a transform list, e.g. `translate(10 20) rotate(45)`.
+pub(crate) fn parse_transform(value: &str) -> Result<Affine2, ParseSvgError> {
+    let error = || ParseSvgError::new(format!("invalid transform '{value}'"));
+    let mut transform = Affine2::IDENTITY;
+
+    for function in value.split(')').map(str::trim).filter(|function| !function.is_empty()) {
+        let (name, arguments) = function.split_once('(').ok_or_else(error)?;
+        let name = name.trim_matches(|c: char| c.is_whitespace() || c == ',');
+        let arguments = parse_numbers(arguments).ok_or_else(error)?;
+
+        let next = match (name, arguments.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => Affine2::from_cols_array(&[a, b, c, d, e, f]),
+            ("translate", &[x]) => Affine2::from_translation(Vec2::new(x, 0.0)),
+            ("translate", &[x, y]) => Affine2::from_translation(Vec2::new(x, y)),
+            ("scale", &[s]) => Affine2::from_scale(Vec2::splat(s)),
+            ("scale", &[x, y]) => Affine2::from_scale(Vec2::new(x, y)),
+            ("rotate", &[angle]) => Affine2::from_angle(angle.to_radians()),
+            ("rotate", &[angle, x, y]) => {
+                let center = Vec2::new(x, y);
+                Affine2::from_translation(center)
+                    * Affine2::from_angle(angle.to_radians())
+                    * Affine2::from_translation(-center)
+            }
+            ("skewX", &[angle]) => Affine2::from_cols_array(&[1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0]),
+            ("skewY", &[angle]) => Affine2::from_cols_array(&[1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0]),
+            _ => return Err(error()),
+        };
+        transform *= next;
+    }
+
+    Ok(transform)
+}
+
+/// Parses a list of numbers separated by whitespace and commas, e.g. the `points` of a polygon.
+pub(crate) fn parse_numbers(value: &str) -> Option<Vec<f32>> {
+    let mut numbers = Numbers::new(value);
+    let mut values = Vec::new();
+
+    loop {
+        numbers.skip_separators();
+        if numbers.peek().is_none() {
+            return Some(values);
+        }
+        values.push(numbers.number()?);
+    }
+}
+
+/// Parses the leading number of a length or coordinate, ignoring its unit.
+pub(crate) fn parse_length(value: &str) -> Option<f32> {
+    Numbers::new(value.trim()).number()
+}
+
+// ---------------< PRIVATE >---------------
+
+fn reflect(ctrl: Option<Vec2>, current: Vec2) -> Vec2 {
+    ctrl.map_or(current, |ctrl| 2.0 * current - ctrl)
+}
+
+/// Lexer for the compact number syntax of SVG, where `1.5.5-2` are the three numbers `1.5 .5 -2`.
+struct Numbers<'a> {
+    source:   &'a str,
+    position: usize,
+}
+
+impl<'a> Numbers<'a> {
+    fn new(source: &'a str) -> Self {
+        Self { source, position: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn advance(&mut self, bytes: usize) {
+        self.position += bytes;
+    }
+
+    /// Skips commas and the whitespace characters defined by SVG, which are all a single byte.
+    fn skip_separators(&mut self) {
+        while self.peek().is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r' | ',')) {
+            self.advance(1);
+        }
+    }
+
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let bytes = self.source.as_bytes();
+        let start = self.position;
+        let mut end = start;
+
+        if matches!(bytes.get(end), Some(b'+' | b'-')) {
+            end += 1;
+        }
+        let mut seen_dot = false;
+        while let Some(&b) = bytes.get(end) {
+            match b {
+                b'0'..=b'9' => {}
+                b'.' if !seen_dot => seen_dot = true,
+                _ => break,
+            }
+            end += 1;
+        }
+        // An exponent, but not the start of a unit like `em` or `ex`
+        if matches!(bytes.get(end), Some(b'e' | b'E'))
+            && bytes.get(end + 1).is_some_and(|b| b.is_ascii_digit() || matches!(b, b'+' | b'-'))
+        {
+            end += 2;
+            while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+                end += 1;
+            }
+        }
+
+        let value = self.source[start..end].parse().ok()?;
+        self.position = end;
+        Some(value)
+    }
+
+    /// Arc flags are single digits that may be written without separators.
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.peek()? {
+            '0' => false,
+            '1' => true,
+            _ => return None,
+        };
+        self.advance(1);
+        Some(flag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PathCommand;
+
+    fn path(data: &str) -> Result<Path, ParseSvgError> {
+        let mut path = Path::new();
+        parse_path_data(data, &mut path).map(|_| path)
+    }
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-4, "{a} != {b}");
+    }
+
+    #[test]
+    fn compact_numbers() {
+        assert_eq!(parse_numbers("1.5.5-2"), Some(vec![1.5, 0.5, -2.0]));
+        assert_eq!(parse_numbers(" 1,2\t3\n-4.\r+5 "), Some(vec![1.0, 2.0, 3.0, -4.0, 5.0]));
+        assert_eq!(parse_numbers("1e2 1E-1 -2e+1"), Some(vec![100.0, 0.1, -20.0]));
+        assert_eq!(parse_numbers("1 x"), None);
+        assert_eq!(parse_numbers(""), Some(vec![]));
+    }
+
+    #[test]
+    fn lengths_ignore_units() {
+        assert_eq!(parse_length("12px"), Some(12.0));
+        assert_eq!(parse_length("2em"), Some(2.0));
+        assert_eq!(parse_length("3ex"), Some(3.0));
+        assert_eq!(parse_length("1e1em"), Some(10.0));
+        assert_eq!(parse_length("50%"), Some(50.0));
+        assert_eq!(parse_length("em"), None);
+    }
+
+    #[test]
+    fn arc_flags_without_separators() {
+        let compact = path("M0 0A5 5 0 1110 0").unwrap();
+        let spaced = path("M 0,0 A 5,5 0 1,1 10,0").unwrap();
+
+        assert_eq!(compact.commands(), spaced.commands());
+        assert_near(compact.current(), Vec2::new(10.0, 0.0));
+        assert!(matches!(compact.commands()[1], PathCommand::CubicTo { .. }));
+
+        assert!(path("M0 0A5 5 0 2 1 10 0").is_err());
+    }
+
+    #[test]
+    fn smooth_cubic_reflects_previous_control() {
+        let path = path("M0 0 C0 1 1 1 1 0 S2 -1 2 0").unwrap();
+        let PathCommand::CubicTo { ctrl1, .. } = path.commands()[2] else {
+            panic!("expected a cubic");
+        };
+        assert_near(ctrl1, Vec2::new(1.0, -1.0));
+
+        // Without a previous cubic the first control point is the current point
+        let path = self::path("M0 0 L1 0 S2 1 3 0").unwrap();
+        let PathCommand::CubicTo { ctrl1, .. } = path.commands()[2] else {
+            panic!("expected a cubic");
+        };
+        assert_near(ctrl1, Vec2::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn smooth_quadratic_reflects_previous_control() {
+        let absolute = path("M0 0 Q1 1 2 0 T4 0 T6 0").unwrap();
+        let relative = path("m0 0 q1 1 2 0 t2 0 t2 0").unwrap();
+        assert_eq!(absolute.commands(), relative.commands());
+
+        let controls: Vec<Vec2> = absolute
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                PathCommand::QuadraticTo { ctrl, .. } => Some(*ctrl),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(controls, [Vec2::new(1.0, 1.0), Vec2::new(3.0, -1.0), Vec2::new(5.0, 1.0)]);
+    }
+
+    #[test]
+    fn implicit_commands() {
+        let path = path("M0 0 1 0 1 1h-1z").unwrap();
+        assert_eq!(
+            path.commands(),
+            [
+                PathCommand::MoveTo(Vec2::ZERO),
+                PathCommand::LineTo(Vec2::new(1.0, 0.0)),
+                PathCommand::LineTo(Vec2::new(1.0, 1.0)),
+                PathCommand::LineTo(Vec2::new(0.0, 1.0)),
+                PathCommand::Close,
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_path_data() {
+        for data in ["0 0", "M0", "M0 0 L", "M0 0 X 1 1", "M0\u{a0}0 L 5 5", "M0 0 L1 1é", "M∞ 0"] {
+            assert!(path(data).is_err(), "'{data}' should be rejected");
+        }
+    }
+
+    #[test]
+    fn transform_lists() {
+        let transform = parse_transform("translate(10 20) scale(2)").unwrap();
+        assert_near(transform.transform_point2(Vec2::ONE), Vec2::new(12.0, 22.0));
+
+        let transform = parse_transform("rotate(90, 1, 1)").unwrap();
+        assert_near(transform.transform_point2(Vec2::new(2.0, 1.0)), Vec2::new(1.0, 2.0));
+
+        let transform = parse_transform("matrix(1 0 0 1 5 6),skewX(45)").unwrap();
+        assert_near(transform.transform_point2(Vec2::new(0.0, 1.0)), Vec2::new(6.0, 7.0));
+
+        let transform = parse_transform("scale(2 3) translate(1)").unwrap();
+        assert_near(transform.transform_point2(Vec2::ZERO), Vec2::new(2.0, 0.0));
+
+        assert_eq!(parse_transform("").unwrap(), Affine2::IDENTITY);
+        for value in ["translate(1 2 3)", "spin(1)", "scale", "rotate(a)"] {
+            assert!(parse_transform(value).is_err(), "'{value}' should be rejected");
+        }
+    }
+}
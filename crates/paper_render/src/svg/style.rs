@@ -0,0 +1,439 @@
+use hashbrown::HashMap;
+use log::debug;
+use paper_math::{Affine2, Rect, Vec2};
+
+use super::{
+    path_data::{parse_length, parse_transform},
+    xml::Element,
+};
+use crate::{FillRule, LineCap, LineJoin, Stroke};
+
+/// Limit for chains of gradients referencing each other through `href`.
+const MAX_HREF_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Paint {
+    None,
+    Color([f32; 4]),
+    /// Id of a gradient element.
+    Gradient(String),
+}
+
+/// The inherited presentation properties of an element.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Style {
+    pub fill: Paint,
+    pub fill_opacity: f32,
+    pub fill_rule: FillRule,
+    pub stroke: Paint,
+    pub stroke_opacity: f32,
+    pub stroke_width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub miter_limit: f32,
+    pub opacity: f32,
+    pub display: bool,
+    pub visible: bool,
+}
+
+impl Style {
+    /// The style of `element` inheriting from its parent's. The `style` attribute takes precedence
+    /// over presentation attributes.
+    pub fn inherit(&self, element: &Element) -> Self {
+        let mut style = self.clone();
+        // Opacity is not inherited, it multiplies with the opacity of the group instead
+        style.opacity = 1.0;
+
+        for (name, value) in &element.attributes {
+            style.apply(name, value);
+        }
+        if let Some(declarations) = element.attribute("style") {
+            for (name, value) in declarations.split(';').filter_map(|declaration| declaration.split_once(':')) {
+                style.apply(name.trim(), value.trim());
+            }
+        }
+
+        style.opacity *= self.opacity;
+        style
+    }
+
+    pub fn stroke(&self) -> Stroke {
+        Stroke::new(self.stroke_width).with_join(self.join).with_cap(self.cap).with_miter_limit(self.miter_limit)
+    }
+
+    // ---------------< PRIVATE >---------------
+
+    fn apply(&mut self, name: &str, value: &str) {
+        match name {
+            "fill" => self.fill = parse_paint(value).unwrap_or_else(|| self.fill.clone()),
+            "fill-opacity" => self.fill_opacity = parse_opacity(value).unwrap_or(self.fill_opacity),
+            "fill-rule" => self.fill_rule = if value == "evenodd" { FillRule::EvenOdd } else { FillRule::NonZero },
+            "stroke" => self.stroke = parse_paint(value).unwrap_or_else(|| self.stroke.clone()),
+            "stroke-opacity" => self.stroke_opacity = parse_opacity(value).unwrap_or(self.stroke_opacity),
+            "stroke-width" => self.stroke_width = parse_length(value).unwrap_or(self.stroke_width),
+            "stroke-linejoin" => {
+                self.join = match value {
+                    "round" => LineJoin::Round,
+                    "bevel" => LineJoin::Bevel,
+                    _ => LineJoin::Miter,
+                }
+            }
+            "stroke-linecap" => {
+                self.cap = match value {
+                    "round" => LineCap::Round,
+                    "square" => LineCap::Square,
+                    _ => LineCap::Butt,
+                }
+            }
+            "stroke-miterlimit" => self.miter_limit = parse_length(value).unwrap_or(self.miter_limit),
+            "opacity" => self.opacity = parse_opacity(value).unwrap_or(self.opacity),
+            "display" => self.display = value != "none",
+            "visibility" => self.visible = value == "visible",
+            _ => {}
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fill: Paint::Color([0.0, 0.0, 0.0, 1.0]),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke: Paint::None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            opacity: 1.0,
+            display: true,
+            visible: true,
+        }
+    }
+}
+
+/// Linear and radial gradients, approximated by evaluating them at every vertex.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Gradient {
+    kind:      GradientKind,
+    bounding:  bool,
+    transform: Affine2,
+    stops:     Vec<(f32, [f32; 4])>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GradientKind {
+    Linear { start: Vec2, end: Vec2 },
+    Radial { center: Vec2, radius: f32 },
+}
+
+impl Gradient {
+    /// Resolves the gradient with the given id, following `href` references for missing stops and
+    /// attributes.
+    pub fn resolve(id: &str, elements: &HashMap<&str, &Element>) -> Option<Self> {
+        let mut chain = Vec::new();
+        let mut next = Some(id);
+        while let Some(id) = next.filter(|_| chain.len() < MAX_HREF_DEPTH) {
+            let element = *elements.get(id)?;
+            chain.push(element);
+            next =
+                element.attribute("href").or(element.attribute("xlink:href")).and_then(|href| href.strip_prefix('#'));
+        }
+
+        let attribute = |name: &str| chain.iter().find_map(|element| element.attribute(name));
+        let length = |name: &str, default: f32| attribute(name).and_then(parse_fraction).unwrap_or(default);
+
+        let kind = match chain.first()?.name.as_str() {
+            "linearGradient" => GradientKind::Linear {
+                start: Vec2::new(length("x1", 0.0), length("y1", 0.0)),
+                end:   Vec2::new(length("x2", 1.0), length("y2", 0.0)),
+            },
+            "radialGradient" => GradientKind::Radial {
+                center: Vec2::new(length("cx", 0.5), length("cy", 0.5)),
+                radius: length("r", 0.5),
+            },
+            name => {
+                debug!("Unsupported paint server '{name}'");
+                return None;
+            }
+        };
+
+        let stops = chain.iter().map(|element| stops(element)).find(|stops| !stops.is_empty()).unwrap_or_default();
+
+        Some(Self {
+            kind,
+            bounding: attribute("gradientUnits") != Some("userSpaceOnUse"),
+            transform: attribute("gradientTransform").and_then(|value| parse_transform(value).ok()).unwrap_or_default(),
+            stops,
+        })
+    }
+
+    /// The color at `point` of a shape with the given bounds.
+    pub fn color_at(&self, point: Vec2, bounds: &Rect) -> [f32; 4] {
+        let mut point = point;
+        if self.bounding {
+            let size = bounds.size().max(Vec2::splat(f32::EPSILON));
+            point = (point - bounds.min) / size;
+        }
+        let point = self.transform.inverse().transform_point2(point);
+
+        let offset = match self.kind {
+            GradientKind::Linear { start, end } => {
+                let direction = end - start;
+                let length = direction.length_squared();
+                if length <= f32::EPSILON { 1.0 } else { (point - start).dot(direction) / length }
+            }
+            GradientKind::Radial { center, radius } => point.distance(center) / radius.max(f32::EPSILON),
+        };
+
+        self.sample(offset.clamp(0.0, 1.0))
+    }
+
+    // ---------------< PRIVATE >---------------
+
+    fn sample(&self, offset: f32) -> [f32; 4] {
+        let Some(first) = self.stops.first() else {
+            return [0.0; 4];
+        };
+        if offset <= first.0 {
+            return first.1;
+        }
+
+        for pair in self.stops.windows(2) {
+            let ((from, a), (to, b)) = (pair[0], pair[1]);
+            if offset <= to {
+                let t = if to - from <= f32::EPSILON { 1.0 } else { (offset - from) / (to - from) };
+                return std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t);
+            }
+        }
+        self.stops.last().map_or([0.0; 4], |stop| stop.1)
+    }
+}
+
+/// Parses a color, `none` and `url(#id)` references.
+pub(crate) fn parse_paint(value: &str) -> Option<Paint> {
+    let value = value.trim();
+    if value == "none" {
+        return Some(Paint::None);
+    }
+    if let Some(reference) = value.strip_prefix("url(") {
+        let id = reference.split(')').next()?.trim().trim_matches(|c| c == '\'' || c == '"');
+        return Some(Paint::Gradient(id.strip_prefix('#').unwrap_or(id).to_string()));
+    }
+    parse_color(value).map(Paint::Color)
+}
+
+/// Parses `#rgb`, `#rrggbb`, `rgb(r, g, b)` and the basic named colors.
+pub(crate) fn parse_color(value: &str) -> Option<[f32; 4]> {
+    let value = value.trim();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits: Vec<u8> = hex.chars().map(|c| c.to_digit(16).map(|d| d as u8)).collect::<Option<_>>()?;
+        let [r, g, b] = match digits.as_slice() {
+            [r, g, b] => [r * 17, g * 17, b * 17],
+            [r1, r2, g1, g2, b1, b2] => [r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2],
+            _ => return None,
+        };
+        return Some([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0]);
+    }
+
+    if let Some(arguments) = value.strip_prefix("rgb(").and_then(|rest| rest.strip_suffix(')')) {
+        let channels: Vec<f32> = arguments
+            .split(',')
+            .map(|channel| {
+                let channel = channel.trim();
+                match channel.strip_suffix('%') {
+                    Some(percent) => percent.parse::<f32>().ok().map(|p| p / 100.0),
+                    None => channel.parse::<f32>().ok().map(|c| c / 255.0),
+                }
+            })
+            .collect::<Option<_>>()?;
+        let [r, g, b] = channels.as_slice() else {
+            return None;
+        };
+        return Some([r.clamp(0.0, 1.0), g.clamp(0.0, 1.0), b.clamp(0.0, 1.0), 1.0]);
+    }
+
+    let rgb: u32 = match value.to_ascii_lowercase().as_str() {
+        "black" => 0x000000,
+        "white" => 0xffffff,
+        "red" => 0xff0000,
+        "lime" => 0x00ff00,
+        "green" => 0x008000,
+        "blue" => 0x0000ff,
+        "yellow" => 0xffff00,
+        "cyan" | "aqua" => 0x00ffff,
+        "magenta" | "fuchsia" => 0xff00ff,
+        "gray" | "grey" => 0x808080,
+        "silver" => 0xc0c0c0,
+        "maroon" => 0x800000,
+        "olive" => 0x808000,
+        "navy" => 0x000080,
+        "purple" => 0x800080,
+        "teal" => 0x008080,
+        "orange" => 0xffa500,
+        _ => {
+            debug!("Unsupported color '{value}'");
+            return None;
+        }
+    };
+    let [r, g, b] = [(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8].map(|c| c as f32 / 255.0);
+    Some([r, g, b, 1.0])
+}
+
+// ---------------< PRIVATE >---------------
+
+fn parse_opacity(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let opacity = match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+        None => value.parse().ok()?,
+    };
+    Some(opacity.clamp(0.0, 1.0))
+}
+
+/// A gradient coordinate, percentages are fractions.
+fn parse_fraction(value: &str) -> Option<f32> {
+    match value.trim().strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok().map(|p| p / 100.0),
+        None => parse_length(value),
+    }
+}
+
+fn stops(element: &Element) -> Vec<(f32, [f32; 4])> {
+    let mut previous = 0.0;
+
+    element
+        .children
+        .iter()
+        .filter(|child| child.name == "stop")
+        .map(|stop| {
+            let style = stop.attribute("style").unwrap_or_default();
+            let property = |name: &str| {
+                style
+                    .split(';')
+                    .filter_map(|declaration| declaration.split_once(':'))
+                    .find(|(key, _)| key.trim() == name)
+                    .map(|(_, value)| value.trim())
+                    .or_else(|| stop.attribute(name))
+            };
+
+            // Offsets never decrease
+            let offset = stop.attribute("offset").and_then(parse_fraction).unwrap_or(0.0).clamp(previous, 1.0);
+            previous = offset;
+
+            let mut color = property("stop-color").and_then(parse_color).unwrap_or([0.0, 0.0, 0.0, 1.0]);
+            color[3] *= property("stop-opacity").and_then(parse_opacity).unwrap_or(1.0);
+            (offset, color)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::xml;
+
+    fn assert_color(actual: Option<[f32; 4]>, expected: [f32; 4]) {
+        let actual = actual.expect("color should parse");
+        assert!(actual.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-3), "{actual:?} != {expected:?}");
+    }
+
+    fn resolve(source: &str, id: &str) -> Option<Gradient> {
+        let root = xml::parse(source).unwrap();
+        let elements = root
+            .descendants()
+            .into_iter()
+            .filter_map(|element| element.attribute("id").map(|id| (id, element)))
+            .collect();
+        Gradient::resolve(id, &elements)
+    }
+
+    #[test]
+    fn hex_colors() {
+        assert_color(parse_color("#f00"), [1.0, 0.0, 0.0, 1.0]);
+        assert_color(parse_color("#00FF80"), [0.0, 1.0, 128.0 / 255.0, 1.0]);
+        assert_color(parse_color(" #abc "), [0xaa as f32 / 255.0, 0xbb as f32 / 255.0, 0xcc as f32 / 255.0, 1.0]);
+        for value in ["#ff", "#ffff", "#ggg", "#", "#fé0"] {
+            assert_eq!(parse_color(value), None, "'{value}' should be rejected");
+        }
+    }
+
+    #[test]
+    fn rgb_colors() {
+        assert_color(parse_color("rgb(255, 0, 51)"), [1.0, 0.0, 0.2, 1.0]);
+        assert_color(parse_color("rgb(100%, 50%, 0%)"), [1.0, 0.5, 0.0, 1.0]);
+        assert_color(parse_color("rgb(300, -5, 0)"), [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(parse_color("rgb(1, 2)"), None);
+        assert_eq!(parse_color("rgb(1, 2, x)"), None);
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_color(parse_color("Navy"), [0.0, 0.0, 128.0 / 255.0, 1.0]);
+        assert_color(parse_color("grey"), parse_color("gray").unwrap());
+        assert_eq!(parse_color("transparentish"), None);
+    }
+
+    #[test]
+    fn paints() {
+        assert_eq!(parse_paint("none"), Some(Paint::None));
+        assert_eq!(parse_paint("url(#fade)"), Some(Paint::Gradient("fade".to_string())));
+        assert_eq!(parse_paint("url('#fade') red"), Some(Paint::Gradient("fade".to_string())));
+        assert_eq!(parse_paint("red"), Some(Paint::Color([1.0, 0.0, 0.0, 1.0])));
+    }
+
+    #[test]
+    fn style_attribute_overrides_presentation_attributes() {
+        let root = xml::parse(r#"<g fill="red" style="fill: blue; opacity: 50%" stroke-width="3"/>"#).unwrap();
+        let style = Style::default().inherit(&root);
+
+        assert_eq!(style.fill, Paint::Color([0.0, 0.0, 1.0, 1.0]));
+        assert_eq!(style.opacity, 0.5);
+        assert_eq!(style.stroke_width, 3.0);
+    }
+
+    #[test]
+    fn gradient_href_chain() {
+        let source = r##"<svg>
+            <linearGradient id="base">
+                <stop offset="0" stop-color="#000"/>
+                <stop offset="100%" style="stop-color: #fff; stop-opacity: 0.5"/>
+            </linearGradient>
+            <linearGradient id="units" href="#base" x2="10" gradientUnits="userSpaceOnUse"/>
+            <linearGradient id="outer" xlink:href="#units" x1="0"/>
+        </svg>"##;
+        let gradient = resolve(source, "outer").unwrap();
+
+        // Stops come from `base` and the user space coordinates from `units`
+        let bounds = Rect::new(Vec2::ZERO, Vec2::ONE);
+        assert_color(Some(gradient.color_at(Vec2::new(5.0, 0.0), &bounds)), [0.5, 0.5, 0.5, 0.75]);
+        assert_color(Some(gradient.color_at(Vec2::new(20.0, 0.0), &bounds)), [1.0, 1.0, 1.0, 0.5]);
+        assert_color(Some(gradient.color_at(Vec2::new(-1.0, 0.0), &bounds)), [0.0, 0.0, 0.0, 1.0]);
+
+        assert!(resolve(source, "missing").is_none());
+    }
+
+    #[test]
+    fn cyclic_href_chain_terminates() {
+        let source = r##"<svg>
+            <radialGradient id="a" href="#b"/>
+            <radialGradient id="b" href="#a"><stop offset="0" stop-color="red"/></radialGradient>
+        </svg>"##;
+        let gradient = resolve(source, "a").unwrap();
+
+        assert_color(Some(gradient.color_at(Vec2::ZERO, &Rect::ZERO)), [1.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn bounding_box_gradient() {
+        let source = r##"<svg><linearGradient id="g" x2="0" y2="1">
+            <stop offset="0" stop-color="black"/><stop offset="1" stop-color="white"/>
+        </linearGradient></svg>"##;
+        let gradient = resolve(source, "g").unwrap();
+        let bounds = Rect::new(Vec2::new(10.0, 10.0), Vec2::new(20.0, 30.0));
+
+        assert_color(Some(gradient.color_at(Vec2::new(15.0, 20.0), &bounds)), [0.5, 0.5, 0.5, 1.0]);
+    }
+}
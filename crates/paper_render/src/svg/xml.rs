@@ -0,0 +1,306 @@
+use crate::ParseSvgError;
+
+/// An XML element with its attributes and child elements, text content is not kept.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct Element {
+    pub name:       String,
+    pub attributes: Vec<(String, String)>,
+    pub children:   Vec<Element>,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// All elements of the tree, depth first.
+    pub fn descendants(&self) -> Vec<&Element> {
+        let mut elements = vec![self];
+        let mut i = 0;
+        while i < elements.len() {
+            elements.extend(&elements[i].children);
+            i += 1;
+        }
+        elements
+    }
+}
+
+/// Parses the root element of a document.
+///
+/// This is a small reader for the subset of XML found in SVG files. Declarations, comments, doctypes
+/// and CDATA sections are skipped and namespaces are not resolved, an `svg:` prefix is dropped from
+/// element names.
+pub(crate) fn parse(source: &str) -> Result<Element, ParseSvgError> {
+    let mut reader = Reader { source, position: 0 };
+    let mut stack: Vec<Element> = Vec::new();
+
+    loop {
+        reader.skip_until('<');
+        if reader.is_done() {
+            return Err(reader.error("unexpected end of document"));
+        }
+
+        if reader.skip_markup()? {
+            continue;
+        }
+
+        if reader.eat("</") {
+            let name = reader.name();
+            reader.skip_whitespace();
+            if !reader.eat(">") {
+                return Err(reader.error("expected '>'"));
+            }
+
+            let element = stack.pop().ok_or_else(|| reader.error("unexpected closing tag"))?;
+            if element.name != element_name(&name) {
+                return Err(reader.error(&format!("expected closing tag for '{}'", element.name)));
+            }
+
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None => return Ok(element),
+            }
+            continue;
+        }
+
+        reader.eat("<");
+        let (element, closed) = reader.start_tag()?;
+        if !closed {
+            stack.push(element);
+            continue;
+        }
+
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(element),
+            None => return Ok(element),
+        }
+    }
+}
+
+// ---------------< PRIVATE >---------------
+
+struct Reader<'a> {
+    source:   &'a str,
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn rest(&self) -> &str {
+        &self.source[self.position..]
+    }
+
+    fn is_done(&self) -> bool {
+        self.position >= self.source.len()
+    }
+
+    fn error(&self, message: &str) -> ParseSvgError {
+        ParseSvgError::new(format!("{message} at byte {}", self.position))
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.position += token.len();
+        }
+        found
+    }
+
+    fn skip_until(&mut self, c: char) {
+        self.position = self.rest().find(c).map_or(self.source.len(), |offset| self.position + offset);
+    }
+
+    fn skip_past(&mut self, token: &str) -> Result<(), ParseSvgError> {
+        let offset = self.rest().find(token).ok_or_else(|| self.error(&format!("expected '{token}'")))?;
+        self.position += offset + token.len();
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start().len();
+        self.position = self.source.len() - trimmed;
+    }
+
+    /// Skips declarations, comments, CDATA sections and doctypes, returns whether anything was skipped.
+    fn skip_markup(&mut self) -> Result<bool, ParseSvgError> {
+        if self.eat("<?") {
+            self.skip_past("?>")?;
+        } else if self.eat("<!--") {
+            self.skip_past("-->")?;
+        } else if self.eat("<![CDATA[") {
+            self.skip_past("]]>")?;
+        } else if self.eat("<!") {
+            // Doctypes may contain an internal subset in brackets
+            let mut depth = 0;
+            for (offset, c) in self.rest().char_indices() {
+                match c {
+                    '[' => depth += 1,
+                    ']' => depth -= 1,
+                    '>' if depth <= 0 => {
+                        self.position += offset + 1;
+                        return Ok(true);
+                    }
+                    _ => {}
+                }
+            }
+            return Err(self.error("unterminated declaration"));
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn name(&mut self) -> String {
+        let end =
+            self.rest().find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=')).unwrap_or(self.rest().len());
+        let name = self.rest()[..end].to_string();
+        self.position += end;
+        name
+    }
+
+    /// Reads a start tag after its `<`, returns the element and whether it was self closing.
+    fn start_tag(&mut self) -> Result<(Element, bool), ParseSvgError> {
+        let name = self.name();
+        if name.is_empty() {
+            return Err(self.error("expected element name"));
+        }
+        let mut element = Element { name: element_name(&name), ..Default::default() };
+
+        loop {
+            self.skip_whitespace();
+            if self.eat("/>") {
+                return Ok((element, true));
+            }
+            if self.eat(">") {
+                return Ok((element, false));
+            }
+
+            let key = self.name();
+            if key.is_empty() {
+                return Err(self.error("expected attribute name"));
+            }
+            self.skip_whitespace();
+            if !self.eat("=") {
+                return Err(self.error("expected '='"));
+            }
+            self.skip_whitespace();
+
+            let quote = self.rest().chars().next().filter(|c| matches!(c, '"' | '\''));
+            let Some(quote) = quote else {
+                return Err(self.error("expected quoted attribute value"));
+            };
+            self.position += 1;
+            let end = self.rest().find(quote).ok_or_else(|| self.error("unterminated attribute value"))?;
+            let value = decode_entities(&self.rest()[..end]);
+            self.position += end + 1;
+
+            element.attributes.push((key, value));
+        }
+    }
+}
+
+fn element_name(name: &str) -> String {
+    name.strip_prefix("svg:").unwrap_or(name).to_string()
+}
+
+fn decode_entities(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+
+        match c {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elements_and_attributes() {
+        let root = parse(
+            r#"<?xml version="1.0"?>
+            <!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" [ <!ENTITY ns "x"> ]>
+            <!-- comment with <tags> -->
+            <svg:svg width='10' height="20">
+                <g id="a"><path d="M0 0"/></g>
+                text is ignored <![CDATA[ <not-an-element/> ]]>
+                <rect x = "1" />
+            </svg:svg>"#,
+        )
+        .unwrap();
+
+        assert_eq!(root.name, "svg");
+        assert_eq!(root.attribute("width"), Some("10"));
+        assert_eq!(root.attribute("height"), Some("20"));
+        assert_eq!(root.attribute("missing"), None);
+
+        let names: Vec<&str> = root.descendants().iter().map(|element| element.name.as_str()).collect();
+        assert_eq!(names, ["svg", "g", "rect", "path"]);
+        assert_eq!(root.children[1].attribute("x"), Some("1"));
+    }
+
+    #[test]
+    fn entity_decoding() {
+        assert_eq!(decode_entities("a &lt;b&gt; &amp;&quot;&apos;"), "a <b> &\"'");
+        assert_eq!(decode_entities("&#65;&#x42;&#x1F600;"), "AB\u{1F600}");
+        assert_eq!(decode_entities("&unknown; &#xZZ; &#1114112; & &amp"), "&unknown; &#xZZ; &#1114112; & &amp");
+
+        let root = parse(r#"<svg title="x &lt; y &amp;&amp; é"/>"#).unwrap();
+        assert_eq!(root.attribute("title"), Some("x < y && é"));
+    }
+
+    #[test]
+    fn malformed_documents() {
+        for source in [
+            "",
+            "no markup",
+            "<svg>",
+            "<svg><g></svg>",
+            "<svg></g>",
+            "</svg>",
+            "<svg><g></g>",
+            "<svg a=1/>",
+            "<svg a/>",
+            "<svg a=\"1/>",
+            "< svg/>",
+            "<svg><!-- unterminated </svg>",
+            "<!DOCTYPE svg [ <svg/>",
+            "<?xml version=\"1.0\"",
+        ] {
+            assert!(parse(source).is_err(), "'{source}' should be rejected");
+        }
+    }
+}
@@ -3,6 +3,8 @@ pub enum WindowMode {
     #[default]
     Windowed,
     Fullscreen,
+    /// An undecorated window covering the whole monitor, without changing its video mode.
+    BorderlessFullscreen,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -45,8 +47,12 @@ pub struct WindowConfig {
     pub title:     String,
     pub resizable: bool,
     pub mode:      WindowMode,
+    /// Index of the monitor used for the fullscreen modes, `0` is the primary monitor.
+    pub monitor:   usize,
     pub samples:   Samples,
     pub vsync:     bool,
+    /// Hidden windows can be used to run replays without showing anything.
+    pub visible:   bool,
 }
 
 impl WindowConfig {
@@ -82,6 +88,11 @@ impl WindowConfig {
         self
     }
 
+    pub fn with_monitor(mut self, monitor: usize) -> Self {
+        self.monitor = monitor;
+        self
+    }
+
     pub fn with_samples(mut self, samples: Samples) -> Self {
         self.samples = samples;
         self
@@ -91,6 +102,11 @@ impl WindowConfig {
         self.vsync = vsync;
         self
     }
+
+    pub fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
 }
 
 impl Default for WindowConfig {
@@ -101,8 +117,10 @@ impl Default for WindowConfig {
             title:     "Paper Window".to_string(),
             resizable: false,
             mode:      WindowMode::default(),
+            monitor:   0,
             samples:   Samples::default(),
             vsync:     true,
+            visible:   true,
         }
     }
 }
@@ -0,0 +1,91 @@
+use crate::RgbaImage;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum CursorMode {
+    #[default]
+    Normal,
+    /// The cursor is invisible while over the window but otherwise behaves normally.
+    Hidden,
+    /// The cursor is hidden and locked to the window, mouse movement is unbounded.
+    /// Combine with raw mouse motion for relative input.
+    Locked,
+}
+
+impl CursorMode {
+    pub(crate) fn glfw_mode(&self) -> glfw::CursorMode {
+        match self {
+            CursorMode::Normal => glfw::CursorMode::Normal,
+            CursorMode::Hidden => glfw::CursorMode::Hidden,
+            CursorMode::Locked => glfw::CursorMode::Disabled,
+        }
+    }
+
+    pub(crate) fn from_glfw(mode: glfw::CursorMode) -> Self {
+        match mode {
+            glfw::CursorMode::Normal => CursorMode::Normal,
+            glfw::CursorMode::Hidden => CursorMode::Hidden,
+            glfw::CursorMode::Disabled => CursorMode::Locked,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum CursorIcon {
+    #[default]
+    Arrow,
+    IBeam,
+    Crosshair,
+    Hand,
+    HorizontalResize,
+    VerticalResize,
+}
+
+impl CursorIcon {
+    pub(crate) fn glfw_cursor(&self) -> glfw::StandardCursor {
+        match self {
+            CursorIcon::Arrow => glfw::StandardCursor::Arrow,
+            CursorIcon::IBeam => glfw::StandardCursor::IBeam,
+            CursorIcon::Crosshair => glfw::StandardCursor::Crosshair,
+            CursorIcon::Hand => glfw::StandardCursor::Hand,
+            CursorIcon::HorizontalResize => glfw::StandardCursor::HResize,
+            CursorIcon::VerticalResize => glfw::StandardCursor::VResize,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Cursor {
+    Standard(CursorIcon),
+    /// `hotspot` is in pixels relative to the top-left corner of the image.
+    Custom {
+        image:   RgbaImage,
+        hotspot: (u32, u32),
+    },
+}
+
+impl Cursor {
+    pub fn custom(image: RgbaImage, hotspot: (u32, u32)) -> Self {
+        Cursor::Custom { image, hotspot }
+    }
+
+    pub(crate) fn glfw_cursor(&self) -> glfw::Cursor {
+        match self {
+            Cursor::Standard(icon) => glfw::Cursor::standard(icon.glfw_cursor()),
+            Cursor::Custom { image, hotspot } => {
+                glfw::Cursor::create_from_pixels(image.pixel_image(), hotspot.0, hotspot.1)
+            }
+        }
+    }
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Cursor::Standard(CursorIcon::default())
+    }
+}
+
+impl From<CursorIcon> for Cursor {
+    fn from(icon: CursorIcon) -> Self {
+        Cursor::Standard(icon)
+    }
+}
@@ -0,0 +1,40 @@
+use log::error;
+
+/// An image with 8 bit RGBA pixels, row by row starting at the top-left corner.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RgbaImage {
+    width:  u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Option<Self> {
+        let expected = width as usize * height as usize * 4;
+        if pixels.len() != expected {
+            error!("Invalid RGBA image data length: expected {} but got {}", expected, pixels.len());
+            return None;
+        }
+        Some(Self { width, height, pixels })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub(crate) fn pixel_image(&self) -> glfw::PixelImage {
+        glfw::PixelImage {
+            width:  self.width,
+            height: self.height,
+            pixels: self.pixels.chunks_exact(4).map(|p| u32::from_ne_bytes([p[0], p[1], p[2], p[3]])).collect(),
+        }
+    }
+}
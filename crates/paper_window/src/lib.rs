@@ -1,9 +1,20 @@
 mod config;
+mod cursor;
+mod image;
+mod monitor;
 mod window;
 
 pub use config::*;
+pub use cursor::*;
+pub use image::*;
+pub use monitor::*;
 pub use window::*;
 
 pub mod prelude {
-    pub use crate::config::{Samples, WindowConfig, WindowMode};
+    pub use crate::{
+        config::{Samples, WindowConfig, WindowMode},
+        cursor::{Cursor, CursorIcon, CursorMode},
+        image::RgbaImage,
+        monitor::{Monitor, VideoMode},
+    };
 }
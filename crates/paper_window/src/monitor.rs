@@ -0,0 +1,56 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VideoMode {
+    pub width:        u32,
+    pub height:       u32,
+    pub refresh_rate: u32,
+    pub red_bits:     u32,
+    pub green_bits:   u32,
+    pub blue_bits:    u32,
+}
+
+impl From<glfw::VidMode> for VideoMode {
+    fn from(mode: glfw::VidMode) -> Self {
+        Self {
+            width:        mode.width,
+            height:       mode.height,
+            refresh_rate: mode.refresh_rate,
+            red_bits:     mode.red_bits,
+            green_bits:   mode.green_bits,
+            blue_bits:    mode.blue_bits,
+        }
+    }
+}
+
+/// A connected monitor. The primary monitor always has index `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    pub index:         usize,
+    pub name:          String,
+    pub position:      (i32, i32),
+    /// Area not occupied by task bars or menu bars as `(x, y, width, height)`.
+    pub work_area:     (i32, i32, i32, i32),
+    /// Physical size in millimetres.
+    pub physical_size: (i32, i32),
+    pub content_scale: (f32, f32),
+    pub current_mode:  Option<VideoMode>,
+    pub video_modes:   Vec<VideoMode>,
+}
+
+impl Monitor {
+    pub fn is_primary(&self) -> bool {
+        self.index == 0
+    }
+
+    pub(crate) fn from_glfw(index: usize, monitor: &glfw::Monitor) -> Self {
+        Self {
+            index,
+            name: monitor.get_name().unwrap_or_default(),
+            position: monitor.get_pos(),
+            work_area: monitor.get_workarea(),
+            physical_size: monitor.get_physical_size(),
+            content_scale: monitor.get_content_scale(),
+            current_mode: monitor.get_video_mode().map(Into::into),
+            video_modes: monitor.get_video_modes().into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -1,23 +1,34 @@
 use glad_gl::gl;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use paper_color::Srgba;
 
-use crate::config::{WindowConfig, WindowMode};
+use crate::{
+    Cursor, CursorMode, Monitor, RgbaImage,
+    config::{WindowConfig, WindowMode},
+};
 
 #[derive(Debug)]
 #[cfg(feature = "internal")]
 pub struct Window {
-    pub glfw:     glfw::Glfw,
-    pub p_window: glfw::PWindow,
-    pub events:   glfw::GlfwReceiver<(f64, glfw::WindowEvent)>,
+    pub glfw:      glfw::Glfw,
+    pub p_window:  glfw::PWindow,
+    pub events:    glfw::GlfwReceiver<(f64, glfw::WindowEvent)>,
+    mode:          WindowMode,
+    monitor:       usize,
+    windowed_pos:  (i32, i32),
+    windowed_size: (i32, i32),
 }
 
 #[derive(Debug)]
 #[cfg(not(feature = "internal"))]
 pub struct Window {
-    glfw:     glfw::Glfw,
-    p_window: glfw::PWindow,
-    events:   glfw::GlfwReceiver<(f64, glfw::WindowEvent)>,
+    glfw:          glfw::Glfw,
+    p_window:      glfw::PWindow,
+    events:        glfw::GlfwReceiver<(f64, glfw::WindowEvent)>,
+    mode:          WindowMode,
+    monitor:       usize,
+    windowed_pos:  (i32, i32),
+    windowed_size: (i32, i32),
 }
 
 impl Window {
@@ -36,30 +47,33 @@ impl Window {
         glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
         glfw.window_hint(glfw::WindowHint::Resizable(config.resizable));
         glfw.window_hint(glfw::WindowHint::Samples(config.samples.glfw_samples()));
+        glfw.window_hint(glfw::WindowHint::ScaleToMonitor(true));
+        glfw.window_hint(glfw::WindowHint::Visible(config.visible));
 
         debug!("Creating GLFW window");
 
-        let Some((mut p_window, events)) = glfw.with_primary_monitor(|glfw, m| {
-            glfw.create_window(
-                config.width,
-                config.height,
-                &config.title,
-                m.map_or(glfw::WindowMode::Windowed, |m| match config.mode {
-                    WindowMode::Windowed => glfw::WindowMode::Windowed,
-                    WindowMode::Fullscreen => glfw::WindowMode::FullScreen(m),
-                }),
-            )
-        }) else {
-            error!("Failed to get primary monitor");
+        let Some((mut p_window, events)) =
+            glfw.create_window(config.width, config.height, &config.title, glfw::WindowMode::Windowed)
+        else {
+            error!("Failed to create GLFW window");
             return None;
         };
 
         // Polling
         p_window.set_key_polling(true);
+        p_window.set_char_polling(true);
         p_window.set_cursor_pos_polling(true);
         p_window.set_scroll_polling(true);
         p_window.set_mouse_button_polling(true);
         p_window.set_cursor_enter_polling(true);
+        p_window.set_framebuffer_size_polling(true);
+        p_window.set_size_polling(true);
+        p_window.set_pos_polling(true);
+        p_window.set_focus_polling(true);
+        p_window.set_iconify_polling(true);
+        p_window.set_maximize_polling(true);
+        p_window.set_content_scale_polling(true);
+        p_window.set_close_polling(true);
 
         debug!("Loading OpenGL function pointers");
 
@@ -76,19 +90,27 @@ impl Window {
             });
         };
 
+        let windowed_pos = p_window.get_pos();
+        let windowed_size = p_window.get_size();
+        let mut window =
+            Self { glfw, p_window, events, mode: WindowMode::Windowed, monitor: 0, windowed_pos, windowed_size };
+
         // Global GL configuration
-        glfw.set_swap_interval(if config.vsync { glfw::SwapInterval::Sync(1) } else { glfw::SwapInterval::None });
+        window.set_vsync(config.vsync);
+        window.set_mode(config.mode, config.monitor);
 
         unsafe {
             // gl::Enable(gl::DEPTH_TEST);
             // gl::Enable(gl::CULL_FACE);
             // gl::CullFace(gl::BACK);
             gl::Enable(gl::MULTISAMPLE);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
         }
 
         info!("Window created successfully");
 
-        Some(Self { glfw, p_window, events })
+        Some(window)
     }
 
     pub fn set_clear_color(&self, color: Srgba) {
@@ -102,4 +124,214 @@ impl Window {
             gl::Clear(gl::COLOR_BUFFER_BIT);
         }
     }
+
+    pub fn cursor_mode(&self) -> CursorMode {
+        CursorMode::from_glfw(self.p_window.get_cursor_mode())
+    }
+
+    pub fn set_cursor_mode(&mut self, mode: CursorMode) {
+        debug!("Setting cursor mode to {mode:?}");
+        self.p_window.set_cursor_mode(mode.glfw_mode());
+    }
+
+    /// Returns `false` if raw mouse motion is not supported on this platform.
+    pub fn set_raw_mouse_motion(&mut self, enabled: bool) -> bool {
+        if !self.glfw.supports_raw_motion() {
+            warn!("Raw mouse motion is not supported");
+            return false;
+        }
+        self.p_window.set_raw_mouse_motion(enabled);
+        true
+    }
+
+    pub fn set_cursor(&mut self, cursor: &Cursor) {
+        self.p_window.set_cursor(Some(cursor.glfw_cursor()));
+    }
+
+    /// Cursor position in logical pixels.
+    pub fn cursor_position(&self) -> (f64, f64) {
+        let (x, y) = self.p_window.get_cursor_pos();
+        self.to_logical(x, y)
+    }
+
+    pub fn mode(&self) -> WindowMode {
+        self.mode
+    }
+
+    /// Switches between windowed, fullscreen and borderless fullscreen on the monitor with the given
+    /// index (see [`Window::monitors`]). Leaving a fullscreen mode restores the last windowed geometry.
+    pub fn set_mode(&mut self, mode: WindowMode, monitor: usize) {
+        debug!("Setting window mode to {mode:?} on monitor {monitor}");
+
+        if self.mode == WindowMode::Windowed {
+            self.windowed_pos = self.p_window.get_pos();
+            self.windowed_size = self.p_window.get_size();
+        }
+
+        let p_window = &mut self.p_window;
+        let (x, y) = self.windowed_pos;
+        let (width, height) = self.windowed_size;
+
+        let applied = self.glfw.with_connected_monitors(|_, monitors| {
+            if mode == WindowMode::Windowed {
+                p_window.set_monitor(glfw::WindowMode::Windowed, x, y, width as u32, height as u32, None);
+                p_window.set_decorated(true);
+                return true;
+            }
+
+            let Some(m) = monitors.get(monitor) else {
+                error!("No monitor with index {monitor} connected");
+                return false;
+            };
+            let Some(video_mode) = m.get_video_mode() else {
+                error!("Failed to get video mode of monitor {monitor}");
+                return false;
+            };
+
+            match mode {
+                WindowMode::Fullscreen => p_window.set_monitor(
+                    glfw::WindowMode::FullScreen(m),
+                    0,
+                    0,
+                    video_mode.width,
+                    video_mode.height,
+                    Some(video_mode.refresh_rate),
+                ),
+                _ => {
+                    let (mx, my) = m.get_pos();
+                    p_window.set_decorated(false);
+                    p_window.set_monitor(glfw::WindowMode::Windowed, mx, my, video_mode.width, video_mode.height, None);
+                }
+            }
+            true
+        });
+
+        if applied {
+            self.mode = mode;
+            self.monitor = monitor;
+        }
+    }
+
+    /// All connected monitors, the primary monitor first.
+    pub fn monitors(&mut self) -> Vec<Monitor> {
+        self.glfw.with_connected_monitors(|_, monitors| {
+            monitors.iter().enumerate().map(|(index, monitor)| Monitor::from_glfw(index, monitor)).collect()
+        })
+    }
+
+    /// Index of the monitor used by the current fullscreen mode.
+    pub fn monitor(&self) -> usize {
+        self.monitor
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.p_window.set_title(title);
+    }
+
+    /// Ratio between physical and logical pixels, e.g. `1.5` on a display scaled to 150%.
+    pub fn scale_factor(&self) -> f32 {
+        self.p_window.get_content_scale().0.max(f32::EPSILON)
+    }
+
+    /// Size of the framebuffer in physical pixels.
+    pub fn framebuffer_size(&self) -> (u32, u32) {
+        let (width, height) = self.p_window.get_framebuffer_size();
+        (width as u32, height as u32)
+    }
+
+    /// Size of the framebuffer in logical pixels.
+    pub fn logical_size(&self) -> (f32, f32) {
+        let (width, height) = self.framebuffer_size();
+        let scale = self.scale_factor();
+        (width as f32 / scale, height as f32 / scale)
+    }
+
+    /// Converts a position in screen coordinates, as reported by the platform, to logical pixels.
+    pub fn to_logical(&self, x: f64, y: f64) -> (f64, f64) {
+        let (width, _) = self.p_window.get_size();
+        let (fb_width, _) = self.p_window.get_framebuffer_size();
+        if width == 0 {
+            return (x, y);
+        }
+        let ratio = fb_width as f64 / width as f64 / self.scale_factor() as f64;
+        (x * ratio, y * ratio)
+    }
+
+    /// Size of the window in screen coordinates. Depending on the platform these are either
+    /// physical or logical pixels, see [`Window::framebuffer_size`] and [`Window::logical_size`].
+    pub fn size(&self) -> (u32, u32) {
+        let (width, height) = self.p_window.get_size();
+        (width as u32, height as u32)
+    }
+
+    /// Resizes the window. Only has an effect in [`WindowMode::Windowed`].
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        if self.mode == WindowMode::Windowed {
+            self.p_window.set_size(width as i32, height as i32);
+        } else {
+            self.windowed_size = (width as i32, height as i32);
+        }
+    }
+
+    pub fn position(&self) -> (i32, i32) {
+        self.p_window.get_pos()
+    }
+
+    /// Moves the window. Only has an effect in [`WindowMode::Windowed`].
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        if self.mode == WindowMode::Windowed {
+            self.p_window.set_pos(x, y);
+        } else {
+            self.windowed_pos = (x, y);
+        }
+    }
+
+    pub fn set_size_limits(&mut self, min: Option<(u32, u32)>, max: Option<(u32, u32)>) {
+        self.p_window.set_size_limits(
+            min.map(|(width, _)| width),
+            min.map(|(_, height)| height),
+            max.map(|(width, _)| width),
+            max.map(|(_, height)| height),
+        );
+    }
+
+    /// Sets the window icon. The platform picks the image closest to the size it needs, an empty
+    /// slice restores the default icon.
+    pub fn set_icon(&mut self, images: &[RgbaImage]) {
+        self.p_window.set_icon_from_pixels(images.iter().map(RgbaImage::pixel_image).collect());
+    }
+
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.glfw.set_swap_interval(if vsync { glfw::SwapInterval::Sync(1) } else { glfw::SwapInterval::None });
+    }
+
+    pub fn minimize(&mut self) {
+        self.p_window.iconify();
+    }
+
+    pub fn maximize(&mut self) {
+        self.p_window.maximize();
+    }
+
+    pub fn restore(&mut self) {
+        self.p_window.restore();
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        self.p_window.is_iconified()
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        self.p_window.is_maximized()
+    }
+
+    pub fn request_attention(&mut self) {
+        self.p_window.request_attention();
+    }
+
+    pub fn set_viewport(&self, width: u32, height: u32) {
+        unsafe {
+            gl::Viewport(0, 0, width as i32, height as i32);
+        }
+    }
 }
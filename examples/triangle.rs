@@ -14,8 +14,9 @@ fn main() {
     app.add_entity(Entity::MeshMaterial { mesh_id, material_id, transform: tri.transform() });
 
     app.add_entity(Entity::Primitive {
-        shape: Shape2D::Triangle { pos: Vec2::new(-200.0, 0.0), size: Vec2::splat(150.0) },
-        color: PINK_RED,
+        shape:  Shape2D::Triangle { pos: Vec2::new(-200.0, 0.0), size: Vec2::splat(150.0) },
+        color:  PINK_RED,
+        stroke: Some((Stroke::new(6.0).with_join(LineJoin::Round), WHITE)),
     });
 
     app.run();
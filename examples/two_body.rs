@@ -11,13 +11,15 @@ impl PaperApp for TwoBodyApp {
         let pos_2 = Vec2 { x: -100.0, y: 0.0 };
 
         let entity_1 = cmd.add_entity(Entity::Primitive {
-            shape: Shape2D::Circle { pos: pos_1, radius: 15.0, segments: 16 },
-            color: WHITE,
+            shape:  Shape2D::Circle { pos: pos_1, radius: 15.0, segments: 16 },
+            color:  WHITE,
+            stroke: None,
         });
 
         let entity_2 = cmd.add_entity(Entity::Primitive {
-            shape: Shape2D::Circle { pos: pos_2, radius: 15.0, segments: 16 },
-            color: WHITE,
+            shape:  Shape2D::Circle { pos: pos_2, radius: 15.0, segments: 16 },
+            color:  WHITE,
+            stroke: None,
         });
 
         let body_1 = Body { id: entity_1, position: pos_1, velocity: Vec2::new(0.0, -15.0), mass: 700000.0 };